@@ -0,0 +1,123 @@
+// Bet journal on the command line, kept in a JSON file in the same format as the app's journal export,
+// so a file exported from the app can be updated here and imported back (bets are matched, not duplicated).
+//
+//   cargo run --bin journal -- FILE list
+//   cargo run --bin journal -- FILE add SELECTION ODDS STAKE [--prob PCT] [--kelly FRACTION] [--market NAME] [--book NAME]
+//                                      [--lay] [--commission PCT]
+//   cargo run --bin journal -- FILE settle ID open|won|lost|push|void
+//   cargo run --bin journal -- FILE import OTHER.json
+//
+// ODDS are decimal. For --lay, ODDS are the lay price and STAKE the backer's stake. A missing FILE is an empty journal;
+// a missing OTHER.json is an error.
+
+#[cfg(target_arch = "wasm32")]
+fn main() {}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {
+    use edgerunner::journal::{add_bet, merge_bets, BetRecord, BetStatus};
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|a| a == "-h" || a == "--help") { usage(""); }
+    let (path, cmd, rest) = match args.as_slice() {
+        [path, cmd, rest @ ..] => (path.as_str(), cmd.as_str(), rest),
+        _ => usage("FILE and a command are required"),
+    };
+    let mut bets = load(path);
+    match (cmd, rest) {
+        ("list", []) => {
+            for b in &bets {
+                let pl = b.profit().map(|pl| format!("{:+.2}", pl)).unwrap_or_default();
+                let side = if b.lay { "lay" } else { "" };
+                println!("{:>4}  {:<24} {:<12} {:>3} {:>7.3} {:>9.2}  {:<5} {:>9}", b.id, b.selection, b.book, side, b.odds, b.stake, b.status.label(), pl);
+            }
+            let settled: Vec<f64> = bets.iter().filter_map(|b| b.profit()).collect();
+            let open = bets.iter().filter(|b| b.status == BetStatus::Open).count();
+            println!("{} bets, {} open; settled P&L {:+.2}", bets.len(), open, settled.iter().sum::<f64>());
+        }
+        ("add", [selection, odds, stake, opts @ ..]) => {
+            let odds = number(odds, "ODDS");
+            let stake = number(stake, "STAKE");
+            if odds <= 1.0 || stake <= 0.0 { usage("ODDS must be above 1 and STAKE above 0"); }
+            let (mut prob, mut kelly, mut market, mut book) = (None, 0.0, String::new(), String::new());
+            let (mut lay, mut commission) = (false, 0.0);
+            let mut opts = opts.iter();
+            while let Some(opt) = opts.next() {
+                if opt == "--lay" { lay = true; continue; }
+                let value = opts.next().unwrap_or_else(|| usage(&format!("{} needs a value", opt)));
+                match opt.as_str() {
+                    "--prob" => prob = Some(number(value, "--prob")),
+                    "--commission" => commission = number(value, "--commission"),
+                    "--kelly" => kelly = number(value, "--kelly"),
+                    "--market" => market = value.clone(),
+                    "--book" => book = value.clone(),
+                    _ => usage(&format!("unknown option {}", opt)),
+                }
+            }
+            let mut rec = BetRecord::new(selection.clone(), market, book, odds, 0.0, stake, kelly).with_commission(commission);
+            if lay { rec = rec.laid(); }
+            rec.prob = prob.unwrap_or_else(|| rec.implied_pct());
+            let id = add_bet(&mut bets, rec, now_ms());
+            save(path, &bets);
+            println!("added bet {}", id);
+        }
+        ("settle", [id, status]) => {
+            let id = number(id, "ID") as u64;
+            let status = parse_status(status).unwrap_or_else(|| usage(&format!("unknown status {}", status)));
+            let bet = bets.iter_mut().find(|b| b.id == id).unwrap_or_else(|| usage(&format!("no bet {}", id)));
+            bet.settle(status, now_ms());
+            save(path, &bets);
+            println!("bet {} is {}", id, status.label());
+        }
+        ("import", [other]) => {
+            // unlike FILE, a missing import file is a mistake, not an empty journal
+            if let Err(e) = std::fs::metadata(other) { usage(&format!("cannot read {}: {}", other, e)); }
+            let (added, updated) = merge_bets(&mut bets, load(other));
+            save(path, &bets);
+            println!("imported {} new bets, updated {}", added, updated);
+        }
+        _ => usage(&format!("bad command {}", cmd)),
+    }
+
+    fn parse_status(s: &str) -> Option<BetStatus> {
+        match s.trim().to_lowercase().as_str() {
+            "open" => Some(BetStatus::Open),
+            "won" | "win" => Some(BetStatus::Won),
+            "lost" | "loss" => Some(BetStatus::Lost),
+            "push" => Some(BetStatus::Push),
+            "void" => Some(BetStatus::Void),
+            _ => None,
+        }
+    }
+
+    fn load(path: &str) -> Vec<BetRecord> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| usage(&format!("{} is not a bet journal: {}", path, e))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => usage(&format!("cannot read {}: {}", path, e)),
+        }
+    }
+
+    fn save(path: &str, bets: &[BetRecord]) {
+        let json = serde_json::to_string_pretty(bets).expect("journal serializes");
+        std::fs::write(path, json + "\n").unwrap_or_else(|e| usage(&format!("cannot write {}: {}", path, e)));
+    }
+
+    fn number(s: &str, what: &str) -> f64 {
+        s.trim().parse::<f64>().ok().filter(|v| v.is_finite()).unwrap_or_else(|| usage(&format!("{} must be a number", what)))
+    }
+
+    fn now_ms() -> f64 {
+        std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|d| d.as_millis() as f64).unwrap_or(0.0)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn usage(err: &str) -> ! {
+    if !err.is_empty() { eprintln!("error: {}", err); }
+    eprintln!("usage: journal FILE list");
+    eprintln!("       journal FILE add SELECTION ODDS STAKE [--prob PCT] [--kelly FRACTION] [--market NAME] [--book NAME] [--lay] [--commission PCT]");
+    eprintln!("       journal FILE settle ID open|won|lost|push|void");
+    eprintln!("       journal FILE import OTHER.json");
+    std::process::exit(if err.is_empty() { 0 } else { 2 });
+}
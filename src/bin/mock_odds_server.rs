@@ -0,0 +1,130 @@
+// Mock live-odds server for development and tests.
+//
+//   cargo run --bin mock_odds_server -- [ADDR] [--interval MS]
+//
+// Listens on ADDR (default 127.0.0.1:9001; port 0 picks a free one) and prints the ws:// URL on the first
+// line of stdout. Each connection gets a snapshot of its subscribed ids on every subscribe, then random-walk
+// price updates every MS milliseconds (default 1000).
+#[cfg(not(target_arch = "wasm32"))]
+#[allow(dead_code)] // the app uses the rest of the protocol
+#[path = "../live.rs"]
+mod live;
+
+#[cfg(target_arch = "wasm32")]
+fn main() {}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {
+    use std::io::Write;
+    use std::net::TcpListener;
+    use std::time::Duration;
+
+    let mut addr = String::from("127.0.0.1:9001");
+    let mut interval = Duration::from_millis(1000);
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--interval" => {
+                let ms = args.next().and_then(|v| v.parse::<u64>().ok()).unwrap_or_else(|| usage("--interval needs milliseconds"));
+                interval = Duration::from_millis(ms.max(1));
+            }
+            "-h" | "--help" => usage(""),
+            _ => addr = arg,
+        }
+    }
+
+    let listener = TcpListener::bind(&addr).unwrap_or_else(|e| usage(&format!("cannot listen on {}: {}", addr, e)));
+    let local = listener.local_addr().expect("bound address");
+    println!("mock odds server listening on ws://{}", local);
+    let _ = std::io::stdout().flush();
+
+    for (n, stream) in listener.incoming().enumerate() {
+        let Ok(stream) = stream else { continue };
+        std::thread::spawn(move || {
+            if let Err(e) = server::serve(stream, interval, 0x0dd5_5eed ^ n as u64) {
+                eprintln!("connection closed: {}", e);
+            }
+        });
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn usage(err: &str) -> ! {
+    if !err.is_empty() { eprintln!("error: {}", err); }
+    eprintln!("usage: mock_odds_server [ADDR] [--interval MS]");
+    std::process::exit(if err.is_empty() { 0 } else { 2 });
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod server {
+    use crate::live::{LiveMessage, PriceUpdate};
+    use std::collections::HashMap;
+    use std::io::ErrorKind;
+    use std::net::TcpStream;
+    use std::time::{Duration, Instant};
+    use tungstenite::{Error, Message};
+
+    pub fn serve(stream: TcpStream, interval: Duration, seed: u64) -> Result<(), Box<Error>> {
+        let mut socket = tungstenite::accept(stream).map_err(|e| match e {
+            tungstenite::HandshakeError::Failure(e) => e,
+            tungstenite::HandshakeError::Interrupted(_) => Error::ConnectionClosed,
+        })?;
+        let mut rng = seed;
+        let mut ids: Vec<String> = Vec::new();
+        let mut prices: HashMap<String, f64> = HashMap::new();
+        let mut next_tick = Instant::now() + interval;
+        loop {
+            let wait = next_tick.saturating_duration_since(Instant::now()).max(Duration::from_millis(1));
+            socket.get_mut().set_read_timeout(Some(wait)).map_err(Error::Io)?;
+            match socket.read() {
+                Ok(Message::Text(text)) => {
+                    if let Ok(LiveMessage::Subscribe { ids: wanted }) = serde_json::from_str(&text) {
+                        ids = wanted;
+                        // snapshot of every subscribed id; new ids start from a price derived from the id
+                        let updates = ids.iter().map(|id| {
+                            let odds = *prices.entry(id.clone()).or_insert_with(|| starting_price(id));
+                            PriceUpdate { id: id.clone(), odds }
+                        }).collect();
+                        send(&mut socket, LiveMessage::Prices { updates })?;
+                    }
+                }
+                Ok(Message::Close(_)) => return Ok(()),
+                Ok(_) => {}
+                Err(Error::Io(e)) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+                Err(e) => return Err(e.into()),
+            }
+            if Instant::now() >= next_tick {
+                next_tick += interval;
+                // each id moves with probability 1/2 by up to ±3%
+                let updates: Vec<PriceUpdate> = ids.iter().filter_map(|id| {
+                    let r = next(&mut rng);
+                    if r & 1 == 0 { return None; }
+                    let step = ((r >> 11) as f64 / (1u64 << 53) as f64 - 0.5) * 0.06;
+                    let odds = prices.entry(id.clone()).or_insert_with(|| starting_price(id));
+                    *odds = (((*odds - 1.0) * (1.0 + step)) + 1.0).max(1.01);
+                    *odds = (*odds * 100.0).round() / 100.0;
+                    Some(PriceUpdate { id: id.clone(), odds: *odds })
+                }).collect();
+                if !updates.is_empty() { send(&mut socket, LiveMessage::Prices { updates })?; }
+            }
+        }
+    }
+
+    fn send(socket: &mut tungstenite::WebSocket<TcpStream>, msg: LiveMessage) -> Result<(), Box<Error>> {
+        Ok(socket.send(Message::Text(serde_json::to_string(&msg).expect("serializable message")))?)
+    }
+
+    fn starting_price(id: &str) -> f64 {
+        let mut h = id.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |h, b| (h ^ b as u64).wrapping_mul(0x100_0000_01b3));
+        1.5 + (next(&mut h) % 250) as f64 / 100.0 // 1.50 – 3.99
+    }
+
+    // SplitMix64
+    fn next(state: &mut u64) -> u64 {
+        *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = *state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
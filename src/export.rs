@@ -0,0 +1,96 @@
+// Recommendation export: one schema for every panel's sized bets, written as CSV or JSON with the same columns.
+use crate::odds::{format_american, format_fractional};
+use serde::{Deserialize, Serialize};
+
+// A sized bet from any panel, as exported: probability and Kelly in %, stake in currency, EV per 1 staked.
+// Odds are the price taken. For an exchange lay, stake is the backer's stake, `liability` the amount lost if the
+// runner wins, and Kelly % is the liability as a share of the bankroll.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Recommendation {
+    pub panel: String,
+    pub selection: String,
+    pub market: String,
+    pub book: String,
+    pub odds_decimal: f64,
+    pub odds_american: String,
+    pub odds_fractional: String,
+    pub prob_pct: f64,
+    pub kelly_pct: f64,
+    pub stake: f64,
+    pub ev: f64,
+    #[serde(default)]
+    pub liability: Option<f64>,
+}
+
+impl Recommendation {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(panel: &str, selection: String, market: String, book: String, odds: f64, prob_pct: f64, kelly: f64, stake: f64, ev: f64) -> Self {
+        Recommendation {
+            panel: panel.into(), selection, market, book,
+            odds_decimal: odds, odds_american: format_american(odds), odds_fractional: format_fractional(odds),
+            prob_pct, kelly_pct: 100.0 * kelly, stake, ev, liability: None,
+        }
+    }
+
+    pub fn with_liability(mut self, liability: f64) -> Self { self.liability = Some(liability); self }
+}
+
+pub const RECOMMENDATION_COLUMNS: [&str; 12] = [
+    "panel", "selection", "market", "book", "odds_decimal", "odds_american", "odds_fractional", "prob_pct", "kelly_pct", "stake", "ev", "liability",
+];
+
+// Same columns as the JSON export; text quoted when it holds a comma, quote or newline
+pub fn recommendations_csv(recs: &[Recommendation]) -> String {
+    let quote = |s: &str| if s.contains([',', '"', '\n']) { format!("\"{}\"", s.replace('"', "\"\"")) } else { s.to_string() };
+    let num = |v: f64, dp: usize| if v.is_finite() { format!("{:.*}", dp, v) } else { String::new() };
+    let mut out = RECOMMENDATION_COLUMNS.join(",") + "\n";
+    for r in recs {
+        let cells = [
+            quote(&r.panel), quote(&r.selection), quote(&r.market), quote(&r.book),
+            num(r.odds_decimal, 4), quote(&r.odds_american), quote(&r.odds_fractional),
+            num(r.prob_pct, 2), num(r.kelly_pct, 3), num(r.stake, 2), num(r.ev, 4), r.liability.map_or(String::new(), |l| num(l, 2)),
+        ];
+        out += &(cells.join(",") + "\n");
+    }
+    out
+}
+
+// JSON array of recommendations, one object per row keyed by RECOMMENDATION_COLUMNS
+pub fn recommendations_json(recs: &[Recommendation]) -> String {
+    serde_json::to_string_pretty(recs).unwrap_or_else(|_| "[]".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Vec<Recommendation> {
+        vec![
+            Recommendation::new("Compare", "Chiefs -3".into(), "Week 1, Sunday".into(), "Book \"A\"".into(), 1.91, 54.5, 0.0432, 43.2, 0.04095),
+            Recommendation::new("Parlay", "A + B".into(), String::new(), String::new(), 3.5, 30.0, 0.01, 10.0, f64::NAN),
+            Recommendation::new("Exchange", "Lay Wolves".into(), "Match Odds".into(), "Exchange".into(), 4.0, 80.0, 0.03, 10.0, 0.05).with_liability(30.0),
+        ]
+    }
+
+    #[test]
+    fn csv_quotes_text_and_blanks_missing_numbers() {
+        let csv = recommendations_csv(&sample());
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], RECOMMENDATION_COLUMNS.join(","));
+        assert_eq!(lines[1], "Compare,Chiefs -3,\"Week 1, Sunday\",\"Book \"\"A\"\"\",1.9100,-110,91/100,54.50,4.320,43.20,0.0410,");
+        assert_eq!(lines[2], "Parlay,A + B,,,3.5000,+250,5/2,30.00,1.000,10.00,,");
+        assert_eq!(lines[3], "Exchange,Lay Wolves,Match Odds,Exchange,4.0000,+300,3/1,80.00,3.000,10.00,0.0500,30.00");
+    }
+
+    #[test]
+    fn json_uses_the_csv_columns_and_round_trips() {
+        let recs = sample();
+        let json = recommendations_json(&recs[..1]);
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let keys: Vec<&String> = value[0].as_object().unwrap().keys().collect();
+        let mut columns = RECOMMENDATION_COLUMNS.to_vec();
+        columns.sort();
+        assert_eq!(keys, columns);
+        assert_eq!(serde_json::from_str::<Vec<Recommendation>>(&json).unwrap(), recs[..1].to_vec());
+    }
+}
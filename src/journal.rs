@@ -0,0 +1,149 @@
+// Bet journal records. Shared by the app (kept in localStorage) and the journal CLI (kept in a JSON file);
+// both read and write the same JSON array, so the app's "Export JSON" file is a valid CLI journal and back.
+use serde::{Deserialize, Serialize};
+
+// A bet recorded from a recommendation; stake and odds are what was actually placed
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BetRecord {
+    pub id: u64,
+    pub placed_at: f64, // ms since epoch
+    pub selection: String,
+    pub market: String,
+    pub book: String,
+    pub odds: f64,  // decimal
+    pub prob: f64,  // your % at bet time
+    pub stake: f64,
+    pub kelly: f64, // recommended fraction of bankroll
+    pub status: BetStatus,
+    #[serde(default)]
+    pub settled_at: Option<f64>, // ms since epoch
+    #[serde(default)]
+    pub closing: String, // closing odds, any format
+    #[serde(default)]
+    pub closing_others: String, // closing odds of the market's other outcomes, comma separated (for de-vig)
+    #[serde(default)]
+    pub market_prob: Option<f64>, // market % at bet time; None falls back to the implied %
+    #[serde(default)]
+    pub lay: bool, // exchange lay: odds are the lay price and stake the backer's stake, so the liability is stake × (odds − 1)
+    #[serde(default)]
+    pub commission: f64, // % of net winnings kept by the exchange
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum BetStatus { Open, Won, Lost, Push, Void }
+
+impl BetStatus {
+    pub fn label(self) -> &'static str {
+        match self {
+            BetStatus::Open => "Open",
+            BetStatus::Won => "Won",
+            BetStatus::Lost => "Lost",
+            BetStatus::Push => "Push",
+            BetStatus::Void => "Void",
+        }
+    }
+}
+
+impl BetRecord {
+    pub fn new(selection: String, market: String, book: String, odds: f64, prob: f64, stake: f64, kelly: f64) -> Self {
+        BetRecord { id: 0, placed_at: 0.0, selection, market, book, odds, prob, stake, kelly, status: BetStatus::Open, settled_at: None, closing: String::new(), closing_others: String::new(), market_prob: None, lay: false, commission: 0.0 }
+    }
+
+    pub fn with_market(mut self, pct: f64) -> Self { self.market_prob = Some(pct); self }
+
+    pub fn laid(mut self) -> Self { self.lay = true; self }
+
+    pub fn with_commission(mut self, pct: f64) -> Self { self.commission = pct; self }
+
+    // Amount lost if the bet loses: the stake, or a lay's liability
+    pub fn risk(&self) -> f64 {
+        if self.lay { self.stake * (self.odds - 1.0) } else { self.stake }
+    }
+
+    // Decimal odds on the amount at risk, net of commission
+    pub fn effective_odds(&self) -> f64 {
+        let keep = 1.0 - self.commission / 100.0;
+        if self.lay { 1.0 + keep / (self.odds - 1.0) } else { 1.0 + keep * (self.odds - 1.0) }
+    }
+
+    // Market % of this bet winning implied by its price (a lay wins when the runner loses)
+    pub fn implied_pct(&self) -> f64 {
+        if self.lay { 100.0 - 100.0 / self.odds } else { 100.0 / self.odds }
+    }
+
+    pub fn profit(&self) -> Option<f64> { // settled P&L, None while open
+        match self.status {
+            BetStatus::Open => None,
+            BetStatus::Won => Some(self.risk() * (self.effective_odds() - 1.0)),
+            BetStatus::Lost => Some(-self.risk()),
+            BetStatus::Push | BetStatus::Void => Some(0.0),
+        }
+    }
+
+    pub fn settle(&mut self, status: BetStatus, now: f64) {
+        self.status = status;
+        self.settled_at = if status == BetStatus::Open { None } else { Some(now) };
+    }
+
+    // Ids are per-journal counters, so the same bet in two journals is recognised by when and what was placed
+    fn same_bet(&self, other: &BetRecord) -> bool {
+        self.placed_at == other.placed_at && self.selection == other.selection && self.book == other.book && self.odds == other.odds
+    }
+}
+
+// Append a bet placed at `now` under the next free id; returns the id
+pub fn add_bet(bets: &mut Vec<BetRecord>, mut rec: BetRecord, now: f64) -> u64 {
+    let id = bets.iter().map(|b| b.id).max().unwrap_or(0) + 1;
+    rec.id = id;
+    rec.placed_at = now;
+    bets.push(rec);
+    id
+}
+
+// Merge an imported journal: a record matching an existing bet replaces it and keeps its id, any other record is
+// appended under a new id. Returns (added, updated).
+pub fn merge_bets(bets: &mut Vec<BetRecord>, imported: Vec<BetRecord>) -> (usize, usize) {
+    let (mut added, mut updated) = (0, 0);
+    for mut rec in imported {
+        match bets.iter_mut().find(|b| b.same_bet(&rec)) {
+            Some(b) => {
+                rec.id = b.id;
+                *b = rec;
+                updated += 1;
+            }
+            None => {
+                rec.id = bets.iter().map(|b| b.id).max().unwrap_or(0) + 1;
+                bets.push(rec);
+                added += 1;
+            }
+        }
+    }
+    bets.sort_by(|a, b| a.placed_at.total_cmp(&b.placed_at));
+    (added, updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn journal_import_matches_bets_by_placement_not_by_id() {
+        let bet = |sel: &str, at: f64| { let mut b = BetRecord::new(sel.into(), "ML".into(), "DK".into(), 2.0, 55.0, 10.0, 0.1); b.placed_at = at; b };
+        let mut mine = Vec::new();
+        assert_eq!(add_bet(&mut mine, bet("Chiefs", 0.0), 1_000.0), 1);
+        assert_eq!(add_bet(&mut mine, bet("Ravens", 0.0), 2_000.0), 2);
+        // another browser: its id 1 is a different bet, its id 2 is our Chiefs bet, now settled
+        let mut theirs = vec![bet("Bills", 500.0), bet("Chiefs", 1_000.0)];
+        theirs[0].id = 1;
+        theirs[1].id = 2;
+        theirs[1].settle(BetStatus::Won, 3_000.0);
+        assert_eq!(merge_bets(&mut mine, theirs), (1, 1));
+        let view: Vec<(u64, &str, BetStatus)> = mine.iter().map(|b| (b.id, b.selection.as_str(), b.status)).collect();
+        assert_eq!(view, vec![(3, "Bills", BetStatus::Open), (1, "Chiefs", BetStatus::Won), (2, "Ravens", BetStatus::Open)]);
+        assert_eq!(mine[1].settled_at, Some(3_000.0));
+        // importing the same file again changes nothing
+        let again = mine.clone();
+        assert_eq!(merge_bets(&mut mine, again.clone()), (0, 3));
+        assert_eq!(mine, again);
+    }
+}
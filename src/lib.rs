@@ -0,0 +1,6 @@
+// Library side of edgerunner: the recommendation export schema and the odds formatting it uses, so the files
+// written by the app's Export Recommendations card can be produced or read by other Rust code, and the bet
+// journal records shared by the app and the journal CLI.
+pub mod export;
+pub mod journal;
+pub mod odds;
@@ -0,0 +1,30 @@
+// Live odds protocol, JSON text frames over a WebSocket. Shared by the app and the mock server.
+//
+// client → server  {"type": "subscribe", "ids": ["nfl-kc-ml", "nfl-bal-ml"]}
+// server → client  {"type": "price", "id": "nfl-kc-ml", "odds": 1.68}
+//                  {"type": "prices", "updates": [{"id": "nfl-kc-ml", "odds": 1.68}, ...]}
+//
+// Ids are free-form strings matched against CompareRow.id and the single bet's live id; odds are decimal.
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LiveMessage {
+    Subscribe { ids: Vec<String> },
+    Price { id: String, odds: f64 },
+    Prices { updates: Vec<PriceUpdate> },
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PriceUpdate { pub id: String, pub odds: f64 }
+
+impl LiveMessage {
+    // Price updates carried by a server message (none for a subscribe)
+    pub fn updates(self) -> Vec<PriceUpdate> {
+        match self {
+            LiveMessage::Subscribe { .. } => vec![],
+            LiveMessage::Price { id, odds } => vec![PriceUpdate { id, odds }],
+            LiveMessage::Prices { updates } => updates,
+        }
+    }
+}
@@ -1,9 +1,22 @@
+mod live;
+
 use yew::prelude::*;
 use yew::TargetCast;
-use web_sys::{HtmlInputElement, HtmlSelectElement};
-use std::collections::HashMap;
+use web_sys::{CloseEvent, HtmlAnchorElement, HtmlInputElement, HtmlSelectElement, MessageEvent, WebSocket};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use edgerunner::export::{recommendations_csv, recommendations_json, Recommendation};
+use edgerunner::odds::{format_american, format_fractional};
+use edgerunner::journal::{add_bet, merge_bets, BetRecord, BetStatus};
+use live::{LiveMessage, PriceUpdate};
+use gloo::file::callbacks::FileReader;
+use gloo::storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
 enum OddsFormat {
     Decimal,
     American,
@@ -18,48 +31,673 @@ impl OddsFormat {
             (Self::Fractional, "Fractional"),
         ]
     }
+
+    fn parse(self, s: &str) -> Option<f64> { // decimal odds
+        match self {
+            Self::Decimal => s.trim().parse::<f64>().ok(),
+            Self::American => parse_american(s),
+            Self::Fractional => parse_fractional(s),
+        }
+    }
 }
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
 enum BetSide { OnEvent, OnOpposite }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 struct OutcomeRow { name: String, mkt: f64, yours: f64 }
 
-#[derive(Clone, PartialEq)]
-struct CompareRow { name: String, group: String, odds: String, your: f64 }
+#[derive(Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct CompareRow { name: String, group: String, book: String, odds: String, your: f64, boost: String, boost_cap: String, parlay: bool, id: String } // id: live feed key
 
-#[derive(Clone, PartialEq)]
+// Per-selection numbers for the Compare panel (decimal odds, boosted odds, boost cap as bankroll fraction).
+// `idx` is the best-priced row for the selection; `others` are the same selection at other books.
+struct CompareCalc { idx: usize, d: f64, d_boost: f64, cap: f64, f: f64, ev: f64, avg: f64, others: Vec<(usize, f64)> }
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 struct ThreeRow { name: String, mkt: f64, yours: f64 }
 
+const BETS_KEY: &str = "edgerunner.bets";
+const JOURNAL_KEY: &str = "edgerunner.journal";
+
+// Reporting settings: bankroll the journal started from, and whether settled P&L drives the bankroll input
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+struct JournalSettings { start_bank: f64, auto_bankroll: bool, #[serde(default)] devig: DevigMethod }
+
+impl Default for JournalSettings {
+    fn default() -> Self { JournalSettings { start_bank: 1000.0, auto_bankroll: false, devig: DevigMethod::default() } }
+}
+
+// How the bookmaker margin is removed from a set of implied probabilities
+#[derive(Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+enum DevigMethod { None, #[default] Multiplicative, Additive, Power }
+
+impl DevigMethod {
+    fn all() -> &'static [(Self, &'static str)] {
+        &[
+            (Self::None, "None (raw)"),
+            (Self::Multiplicative, "Multiplicative"),
+            (Self::Additive, "Additive"),
+            (Self::Power, "Power"),
+        ]
+    }
+}
+
+// Closing line value of one bet: (odds CLV as taken/close - 1, probability CLV as fair close prob - taken implied prob).
+// A lay is measured from the layer's side against the runner's closing price: laying shorter than the close is value.
+fn bet_clv(b: &BetRecord, method: DevigMethod) -> Option<(f64, f64)> {
+    let close = parse_any(&b.closing)?;
+    let mut implied = vec![1.0 / close];
+    implied.extend(b.closing_others.split(',').filter_map(parse_any).map(|d| 1.0 / d));
+    let fair = devig(&implied, method)[0];
+    if b.lay { return Some((close / b.odds - 1.0, 1.0 / b.odds - fair)); }
+    Some((b.odds / close - 1.0, fair - 1.0 / b.odds))
+}
+
+// Performance over settled bets (voids excluded from turnover and win rate)
+struct JournalStats {
+    settled: usize,
+    turnover: f64,
+    pnl: f64,
+    open_stake: f64,
+    history: Vec<f64>, // bankroll after each settlement, starting bankroll first
+    bands: Vec<OddsBand>,
+}
+
+struct OddsBand { label: &'static str, bets: usize, wins: usize, implied: f64, pnl: f64 } // implied: sum of 1/odds
+
+const ODDS_BANDS: [(&str, f64, f64); 5] = [
+    ("< 1.50", 1.0, 1.5), ("1.50–2.00", 1.5, 2.0), ("2.00–3.00", 2.0, 3.0), ("3.00–5.00", 3.0, 5.0), ("5.00+", 5.0, f64::INFINITY),
+];
+
+fn journal_stats(bets: &[BetRecord], start_bank: f64) -> JournalStats {
+    let mut settled: Vec<&BetRecord> = bets.iter().filter(|b| b.status != BetStatus::Open).collect();
+    settled.sort_by(|a, b| a.settled_at.unwrap_or(a.placed_at).total_cmp(&b.settled_at.unwrap_or(b.placed_at)));
+    let mut bands: Vec<OddsBand> = ODDS_BANDS.iter().map(|(label, _, _)| OddsBand { label, bets: 0, wins: 0, implied: 0.0, pnl: 0.0 }).collect();
+    let mut history = vec![start_bank];
+    let (mut turnover, mut pnl) = (0.0, 0.0);
+    for b in settled.iter() {
+        let pl = b.profit().unwrap_or(0.0);
+        pnl += pl;
+        history.push(start_bank + pnl);
+        if b.status == BetStatus::Void { continue; }
+        turnover += b.risk();
+        if b.status == BetStatus::Push { continue; }
+        let odds = b.effective_odds();
+        if let Some(band) = ODDS_BANDS.iter().position(|(_, lo, hi)| odds >= *lo && odds < *hi) {
+            let band = &mut bands[band];
+            band.bets += 1;
+            if b.status == BetStatus::Won { band.wins += 1; }
+            band.implied += 1.0 / odds;
+            band.pnl += pl;
+        }
+    }
+    let open_stake = bets.iter().filter(|b| b.status == BetStatus::Open).map(|b| b.risk()).sum();
+    JournalStats { settled: settled.len(), turnover, pnl, open_stake, history, bands }
+}
+
+// Calibration of probability forecasts against settled won/lost results
+struct Calibration { n: usize, brier: f64, log_loss: f64, bins: Vec<(usize, f64, f64)> } // per bin: (count, mean predicted, realized frequency)
+
+const CALIBRATION_BINS: usize = 10;
+
+fn calibration(forecasts: &[(f64, bool)]) -> Calibration {
+    let mut bins = vec![(0usize, 0.0, 0.0); CALIBRATION_BINS];
+    let (mut brier, mut log_loss) = (0.0, 0.0);
+    for &(p, won) in forecasts {
+        let y = if won { 1.0 } else { 0.0 };
+        let pc = p.clamp(1e-6, 1.0 - 1e-6);
+        brier += (p - y).powi(2);
+        log_loss -= y * pc.ln() + (1.0 - y) * (1.0 - pc).ln();
+        let bin = &mut bins[((p * CALIBRATION_BINS as f64) as usize).min(CALIBRATION_BINS - 1)];
+        bin.0 += 1;
+        bin.1 += p;
+        bin.2 += y;
+    }
+    let n = forecasts.len();
+    for bin in bins.iter_mut().filter(|b| b.0 > 0) { bin.1 /= bin.0 as f64; bin.2 /= bin.0 as f64; }
+    let nf = n.max(1) as f64;
+    Calibration { n, brier: brier / nf, log_loss: log_loss / nf, bins }
+}
+
+// Sure bet in one Compare group: best price per selection, stake shares that equalize the payout
+struct SureBet { group: String, legs: Vec<(usize, f64, f64)>, book: f64 } // (row idx, odds, share), sum of 1/odds
+
+// Middle between two lines at different numbers: bet a wins above lo, bet b below hi. `hits` lists every whole-number
+// result in [lo, hi] with its return per 1 staked (both win inside, one pushes on a whole-number endpoint).
+struct Middle { group: String, a: usize, b: usize, shares: (f64, f64), lo: f64, hi: f64, miss: f64, hits: Vec<(f64, f64)> }
+
+#[derive(Clone, Copy, PartialEq)]
+enum LineKind { Over, Under, Spread }
+
+// Pairwise correlation between two Compare selections (name and group), used by the copula allocation.
+// An empty group matches the name in any group as long as it is unique.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+struct CorrRow { a: String, b: String, rho: f64, #[serde(default)] a_group: String, #[serde(default)] b_group: String }
+
+// A Compare selection at its best price, as a leg of the correlated allocation
+#[derive(Clone, PartialEq)]
+struct CorrLeg { group: String, name: String, d: f64, p: f64 }
+
+// Correlated allocation per leg (with the declared correlations and with none), log growth of each,
+// the factor the correlations were scaled by, and correlations that could not be used
+#[derive(PartialEq)]
+struct CorrCalc { correlated: Vec<f64>, independent: Vec<f64>, g_corr: f64, g_indep: f64, shrink: f64, notes: Vec<String> }
+
+// Cash-out offer on an open bet: original stake and odds, your current win %, the offer amount
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+struct CashoutInputs { stake: String, odds: String, prob: f64, offer: String }
+
+// Each-way: win odds, place terms (1/terms of the odds) over `places` places, your win and place (top-K) %
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+struct EachWayInputs { odds: String, terms: u32, places: u32, win: f64, place: f64 }
+
+// Asian handicap: line and odds for the backed side, your % for each goal difference (home - away)
+// from -4 to +4; the end buckets include the tails (≤-4, ≥+4)
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+struct AsianInputs { line: String, odds: String, away: bool, dist: Vec<f64> }
+
+// Elo ratings to win/draw/loss: home rating gets `home_adv` points; with `draws` on, the Davidson model
+// gives a draw share that is `draw_pct` at equal ratings and shrinks as the gap grows
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+struct EloInputs { home: f64, away: f64, home_adv: f64, draws: bool, draw_pct: f64 }
+
+// Poisson goal model: expected goals per side, optional Dixon–Coles low-score correction `rho`,
+// and the total-goals line shown for over/under
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+struct PoissonInputs { home_xg: f64, away_xg: f64, dixon_coles: bool, rho: f64, line: f64 }
+
+// Markets read off a score matrix; `push` is the exact-total probability on whole-number lines
+struct GoalMarkets { home: f64, draw: f64, away: f64, over: f64, push: f64, under: f64, btts: f64 }
+
+const POISSON_MIN_GOALS: usize = 10; // per side; the grid always covers 0..=10
+const POISSON_MAX_GOALS: usize = 100; // and grows until the tail beyond it is below POISSON_TAIL
+const POISSON_TAIL: f64 = 1e-9;
+
+const AH_MIN_GD: i32 = -4;
+const PARLAY_MAX_LEGS: usize = 10; // singles comparison enumerates 2^legs outcomes
+const COPULA_DRAWS: usize = 4000;
+const COPULA_SEED: u64 = 0x5eed_1234;
+const COPULA_MAX_LEGS: usize = 64; // win masks are u64
+
+// Working inputs of every panel, shared through the URL and kept in localStorage (the journal is stored separately).
+// Missing fields take their defaults so older or hand-trimmed links still load.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+struct AppState {
+    market_prob: f64,
+    your_prob: f64,
+    push_prob: f64,
+    position_stake: String,
+    position_odds: String,
+    position_side: BetSide,
+    hedge_odds: String,
+    bet_side: BetSide,
+    odds_format: OddsFormat,
+    odds_input: String,
+    bankroll: String,
+    boost_input: String,
+    boost_cap: String,
+    outcomes: Vec<OutcomeRow>,
+    compares: Vec<CompareRow>,
+    three: Vec<ThreeRow>,
+    arb_stake: String,
+    correlations: Vec<CorrRow>,
+    parlay_adj: f64,
+    cashout: CashoutInputs,
+    each_way: EachWayInputs,
+    asian: AsianInputs,
+    exchange: ExchangeInputs,
+    live_url: String,
+    single_live_id: String, // live feed key for the single bet odds
+    elo: EloInputs,
+    poisson: PoissonInputs,
+}
+
+impl Default for AppState {
+    fn default() -> Self {
+        AppState {
+            market_prob: 60.0,
+            your_prob: 55.0,
+            push_prob: 0.0,
+            position_stake: String::new(),
+            position_odds: String::new(),
+            position_side: BetSide::OnEvent,
+            hedge_odds: String::new(),
+            bet_side: BetSide::OnEvent,
+            odds_format: OddsFormat::Decimal,
+            // Default to blank odds so market % drives implied odds by default.
+            odds_input: String::new(),
+            bankroll: "1000".into(),
+            boost_input: String::new(),
+            boost_cap: String::new(),
+            outcomes: vec![
+                OutcomeRow { name: "A".into(), mkt: 50.0, yours: 60.0 },
+                OutcomeRow { name: "B".into(), mkt: 50.0, yours: 40.0 },
+            ],
+            compares: vec![
+                CompareRow { name: "Selection 1".into(), group: "Market 1".into(), your: 55.0, ..Default::default() },
+            ],
+            three: vec![
+                ThreeRow { name: "Home".into(), mkt: 40.0, yours: 45.0 },
+                ThreeRow { name: "Draw".into(), mkt: 30.0, yours: 25.0 },
+                ThreeRow { name: "Away".into(), mkt: 30.0, yours: 30.0 },
+            ],
+            arb_stake: "100".into(),
+            correlations: Vec::new(),
+            parlay_adj: 0.0,
+            cashout: CashoutInputs { stake: "100".into(), odds: "3.0".into(), prob: 40.0, offer: "110".into() },
+            each_way: EachWayInputs { odds: "8.0".into(), terms: 4, places: 3, win: 15.0, place: 40.0 },
+            asian: AsianInputs {
+                line: "-0.25".into(), odds: "1.95".into(), away: false,
+                dist: vec![1.0, 3.0, 8.0, 18.0, 26.0, 22.0, 13.0, 6.0, 3.0],
+            },
+            exchange: ExchangeInputs::default(),
+            live_url: "ws://127.0.0.1:9001".into(),
+            single_live_id: String::new(),
+            elo: EloInputs { home: 1600.0, away: 1500.0, home_adv: 65.0, draws: true, draw_pct: 26.0 },
+            poisson: PoissonInputs { home_xg: 1.45, away_xg: 1.15, dixon_coles: true, rho: -0.1, line: 2.5 },
+        }
+    }
+}
+
+impl AppState {
+    // Replace shapes the panels index into unchecked
+    fn validated(mut self) -> Self {
+        let d = AppState::default();
+        if self.three.len() != d.three.len() { self.three = d.three; }
+        if self.asian.dist.len() != d.asian.dist.len() { self.asian.dist = d.asian.dist; }
+        self
+    }
+}
+
+const STATE_HASH_PREFIX: &str = "#s=";
+
+// Saved working state: { "version": STATE_VERSION, "state": AppState }
+const STATE_KEY: &str = "edgerunner.state";
+const STATE_VERSION: u64 = 1;
+
+// A named snapshot of the inputs; `scope` limits which panel it covers when loaded
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+struct Preset { name: String, scope: PresetScope, state: AppState }
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+enum PresetScope { All, Single, Multi, Compare, ThreeWay, Asian, Cashout, EachWay, Exchange, Models }
+
+impl PresetScope {
+    fn all() -> &'static [(Self, &'static str)] {
+        &[
+            (Self::All, "All panels"),
+            (Self::Single, "Single Bet"),
+            (Self::Multi, "Multiple Outcomes"),
+            (Self::Compare, "Compare / Parlay"),
+            (Self::ThreeWay, "Three-Way"),
+            (Self::Asian, "Asian Handicap"),
+            (Self::Cashout, "Cash-Out"),
+            (Self::EachWay, "Each-Way"),
+            (Self::Exchange, "Exchange"),
+            (Self::Models, "Probability Models"),
+        ]
+    }
+
+    fn label(self) -> &'static str {
+        Self::all().iter().find(|(s, _)| *s == self).map(|(_, l)| *l).unwrap_or("")
+    }
+
+    // `current` with this scope's fields taken from `preset`; the bankroll is shared by every panel and only
+    // an All-panels preset changes it
+    fn merge(self, current: &AppState, preset: &AppState) -> AppState {
+        let mut s = current.clone();
+        let p = preset.clone();
+        match self {
+            Self::All => return p,
+            Self::Single => {
+                s.market_prob = p.market_prob;
+                s.your_prob = p.your_prob;
+                s.push_prob = p.push_prob;
+                s.position_stake = p.position_stake;
+                s.position_odds = p.position_odds;
+                s.position_side = p.position_side;
+                s.hedge_odds = p.hedge_odds;
+                s.bet_side = p.bet_side;
+                s.odds_format = p.odds_format;
+                s.odds_input = p.odds_input;
+                s.boost_input = p.boost_input;
+                s.boost_cap = p.boost_cap;
+                s.single_live_id = p.single_live_id;
+            }
+            Self::Multi => s.outcomes = p.outcomes,
+            Self::Compare => {
+                s.compares = p.compares;
+                s.arb_stake = p.arb_stake;
+                s.correlations = p.correlations;
+                s.parlay_adj = p.parlay_adj;
+            }
+            Self::ThreeWay => s.three = p.three,
+            Self::Asian => s.asian = p.asian,
+            Self::Cashout => s.cashout = p.cashout,
+            Self::EachWay => s.each_way = p.each_way,
+            Self::Exchange => s.exchange = p.exchange,
+            Self::Models => {
+                s.elo = p.elo;
+                s.poisson = p.poisson;
+            }
+        }
+        s
+    }
+}
+
+const PRESETS_KEY: &str = "edgerunner.presets";
+
+// CompareRow fields a CSV column can map to, with header names recognized automatically
+const CSV_FIELDS: [(&str, &[&str]); 8] = [
+    ("Name", &["name", "selection", "runner", "team", "player", "outcome"]),
+    ("Group", &["group", "market", "event", "game", "match"]),
+    ("Book", &["book", "bookmaker", "sportsbook", "bookie", "site"]),
+    ("Odds", &["odds", "price", "decimal", "line_odds"]),
+    ("Your %", &["your", "your %", "your%", "prob", "probability", "p", "model", "model_prob", "fair_prob"]),
+    ("Boost", &["boost", "boosted", "boosted_odds"]),
+    ("Boost Max Stake", &["boost_cap", "cap", "max_stake", "boost max stake"]),
+    ("Feed ID", &["id", "feed_id", "selection_id", "selectionid"]),
+];
+const CSV_NAME: usize = 0;
+const CSV_GROUP: usize = 1;
+const CSV_BOOK: usize = 2;
+const CSV_ODDS: usize = 3;
+const CSV_PROB: usize = 4;
+const CSV_BOOST: usize = 5;
+const CSV_BOOST_CAP: usize = 6;
+const CSV_ID: usize = 7;
+
+// Exchange market: one row per runner with its back and lay ladders (price, size), best price first.
+// Runners are treated as mutually exclusive; a lay of runner k is a bet on "not k".
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+struct ExchangeRunner { id: u64, name: String, back: Vec<(f64, f64)>, lay: Vec<(f64, f64)>, yours: f64 }
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+struct ExchangeInputs { market: String, commission: f64, runners: Vec<ExchangeRunner> } // commission in % of net winnings
+
+impl Default for ExchangeInputs {
+    fn default() -> Self { ExchangeInputs { market: String::new(), commission: 2.0, runners: Vec::new() } }
+}
+
+// Exchange market book snapshot (Betfair listMarketBook layout, runner names optional)
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum MarketBookFeed { Books(Vec<MarketBook>), Book(MarketBook) }
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MarketBook {
+    #[serde(default)]
+    market_id: String,
+    #[serde(default)]
+    runners: Vec<MarketBookRunner>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MarketBookRunner {
+    selection_id: u64,
+    #[serde(default, alias = "name")]
+    runner_name: Option<String>,
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(default)]
+    ex: MarketBookLadder,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct MarketBookLadder {
+    #[serde(default)]
+    available_to_back: Vec<PriceSize>,
+    #[serde(default)]
+    available_to_lay: Vec<PriceSize>,
+}
+
+#[derive(Deserialize)]
+struct PriceSize { price: f64, size: f64 }
+
+// Odds aggregator feed: events → bookmakers → markets → outcomes (The Odds API v4 layout).
+// Prices come as decimal or American depending on the request's oddsFormat.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OddsApiFeed { Events(Vec<OddsApiEvent>), Event(OddsApiEvent) }
+
+#[derive(Deserialize)]
+struct OddsApiEvent {
+    #[serde(default)]
+    sport_title: String,
+    home_team: String,
+    away_team: String,
+    #[serde(default)]
+    bookmakers: Vec<OddsApiBook>,
+}
+
+#[derive(Deserialize)]
+struct OddsApiBook { key: String, #[serde(default)] title: String, #[serde(default)] markets: Vec<OddsApiMarket> }
+
+#[derive(Deserialize)]
+struct OddsApiMarket { key: String, #[serde(default)] outcomes: Vec<OddsApiOutcome> }
+
+#[derive(Deserialize)]
+struct OddsApiOutcome { name: String, price: f64, #[serde(default)] point: Option<f64> }
+
+// Live price feed: connection status, updates not yet applied to the panels, last price and recent move per id
+#[derive(Clone, PartialEq, Default)]
+struct LiveFeed {
+    status: LiveStatus,
+    seq: u64, // bumped on every message with updates
+    pending: Vec<PriceUpdate>,
+    prices: HashMap<String, f64>,
+    moved: HashMap<String, (f64, f64)>, // (previous, new) odds, cleared LIVE_HIGHLIGHT_MS after the move
+}
+
+#[derive(Clone, PartialEq, Default)]
+enum LiveStatus { #[default] Off, Connecting, Live, Closed(String) }
+
+enum LiveAction { Status(LiveStatus), Updates(Vec<PriceUpdate>), Applied, Expire(String, f64) }
+
+impl Reducible for LiveFeed {
+    type Action = LiveAction;
+
+    fn reduce(self: Rc<Self>, action: LiveAction) -> Rc<Self> {
+        let mut s = (*self).clone();
+        match action {
+            // The close that follows a socket error keeps the error as the reason
+            LiveAction::Status(LiveStatus::Closed(_)) if matches!(s.status, LiveStatus::Closed(_)) => {}
+            LiveAction::Status(status) => s.status = status,
+            LiveAction::Updates(updates) => {
+                for u in updates.iter() {
+                    if let Some(old) = s.prices.insert(u.id.clone(), u.odds) {
+                        if old != u.odds { s.moved.insert(u.id.clone(), (old, u.odds)); }
+                    }
+                }
+                s.pending.extend(updates);
+                s.seq += 1;
+            }
+            LiveAction::Applied => s.pending.clear(),
+            LiveAction::Expire(id, odds) => {
+                if s.moved.get(&id).is_some_and(|m| m.1 == odds) { s.moved.remove(&id); }
+            }
+        }
+        Rc::new(s)
+    }
+}
+
+const LIVE_HIGHLIGHT_MS: u32 = 5_000;
+
+// Recommendations shown per live feed id as (row, fraction of bankroll); the single bet uses row usize::MAX
+type LiveRecs = HashMap<String, Vec<(usize, f64)>>;
+
+// Ids whose odds moved and whose recommendations differ from those shown just before the move
+fn live_changed(moved: &HashMap<String, (f64, f64)>, before: &LiveRecs, now: &LiveRecs) -> Vec<String> {
+    moved.keys()
+        .filter(|id| before.get(*id).is_some_and(|b| b != now.get(*id).unwrap_or(&Vec::new())))
+        .cloned()
+        .collect()
+}
+
+// Open socket and the JS callbacks it holds; dropping it closes the connection and cancels pending highlight expiries
+struct LiveSocket {
+    ws: WebSocket,
+    _on_open: Closure<dyn FnMut(JsValue)>,
+    _on_message: Closure<dyn FnMut(MessageEvent)>,
+    _on_error: Closure<dyn FnMut(JsValue)>,
+    _on_close: Closure<dyn FnMut(CloseEvent)>,
+}
+
+impl Drop for LiveSocket {
+    fn drop(&mut self) {
+        self.ws.set_onopen(None);
+        self.ws.set_onmessage(None);
+        self.ws.set_onclose(None);
+        self.ws.set_onerror(None);
+        let _ = self.ws.close();
+    }
+}
+
 #[function_component(App)]
 fn app() -> Html {
+    // Inputs start from a shared link when the URL carries one, else from the last session
+    let initial = use_memo((), |_| state_from_hash().or_else(load_state).unwrap_or_default());
+
     // Single bet state
-    let market_prob = use_state(|| 60.0_f64); // % market thinks event happens
-    let your_prob = use_state(|| 55.0_f64);   // % you think event happens
-    let bet_side = use_state(|| BetSide::OnEvent);
-    let odds_format = use_state(|| OddsFormat::Decimal);
-    // Default to blank odds so market % drives implied odds by default.
-    let odds_input = use_state(|| String::from(""));
-    let bankroll = use_state(|| String::from("1000"));
+    let market_prob = use_state(|| initial.market_prob); // % market thinks event happens
+    let your_prob = use_state(|| initial.your_prob);     // % you think event happens
+    let push_prob = use_state(|| initial.push_prob);     // % the bet pushes (stake returned)
+    // Open position already held on one side (stake already out of the bankroll)
+    let position_stake = use_state(|| initial.position_stake.clone());
+    let position_odds = use_state(|| initial.position_odds.clone());
+    let position_side = use_state(|| initial.position_side);
+    let hedge_odds = use_state(|| initial.hedge_odds.clone()); // opposite-side price, blank = no-vig complement
+    let bet_side = use_state(|| initial.bet_side);
+    let odds_format = use_state(|| initial.odds_format);
+    let odds_input = use_state(|| initial.odds_input.clone());
+    let bankroll = use_state(|| initial.bankroll.clone());
+    // Optional boost: boosted odds or "+X%" profit boost, paid up to a max stake
+    let boost_input = use_state(|| initial.boost_input.clone());
+    let boost_cap = use_state(|| initial.boost_cap.clone());
 
     // Multi-outcome state
-    let outcomes = use_state(|| vec![
-        OutcomeRow { name: "A".into(), mkt: 50.0, yours: 60.0 },
-        OutcomeRow { name: "B".into(), mkt: 50.0, yours: 40.0 },
-    ]);
+    let outcomes = use_state(|| initial.outcomes.clone());
 
     // Compare bets state (live comparisons across different selections/markets)
-    let compares = use_state(|| vec![
-        CompareRow { name: "Selection 1".into(), group: "Market 1".into(), odds: "".into(), your: 55.0 },
-    ]);
+    let compares = use_state(|| initial.compares.clone());
+
+    // CSV / TSV import into Compare: pasted or loaded text, column overrides per CSV_FIELDS entry, last import report
+    let csv_text = use_state(String::new);
+    let csv_map = use_state(|| vec![None::<Option<usize>>; CSV_FIELDS.len()]); // None = auto-detect, Some(None) = not mapped
+    let csv_append = use_state(|| false);
+    let csv_report = use_state(|| None::<(usize, Vec<String>)>); // (rows imported, row errors)
+    let csv_odds_format = use_state(|| None::<OddsFormat>); // None = any format, detected per cell
+    let csv_reader = use_mut_ref(|| None::<FileReader>);
+
+    // Live odds over WebSocket
+    let live_url = use_state(|| initial.live_url.clone());
+    let single_live_id = use_state(|| initial.single_live_id.clone());
+    let live = use_reducer(LiveFeed::default);
+    let live_socket = use_mut_ref(|| None::<LiveSocket>);
+    let live_shown = use_mut_ref(LiveRecs::new); // recommendations in the last render
+    let live_before = use_mut_ref(LiveRecs::new); // recommendations shown before each id's latest move
+
+    // Odds feed import into Compare
+    let feed_text = use_state(String::new);
+    let feed_replace = use_state(|| false);
+    let feed_report = use_state(|| None::<Result<usize, String>>); // rows imported or the parse error
+    let feed_reader = use_mut_ref(|| None::<FileReader>);
+
+    // Total stake used to show sure-bet and middle splits
+    let arb_stake = use_state(|| initial.arb_stake.clone());
+
+    // Correlations between Compare rows (Gaussian copula)
+    let correlations = use_state(|| initial.correlations.clone());
+
+    // Parlay built from Compare rows: correlation adjustment (%) applied to the joint probability
+    let parlay_adj = use_state(|| initial.parlay_adj);
 
     // Three-way market (exact Kelly for a single event with 3 outcomes)
-    let three = use_state(|| vec![
-        ThreeRow { name: "Home".into(), mkt: 40.0, yours: 45.0 },
-        ThreeRow { name: "Draw".into(), mkt: 30.0, yours: 25.0 },
-        ThreeRow { name: "Away".into(), mkt: 30.0, yours: 30.0 },
-    ]);
+    let three = use_state(|| initial.three.clone());
+
+    // Cash-out evaluator
+    let cashout = use_state(|| initial.cashout.clone());
+
+    // Each-way bet (win part + place part)
+    let each_way = use_state(|| initial.each_way.clone());
+
+    // Asian handicap
+    let asian = use_state(|| initial.asian.clone());
+
+    // Exchange market book: runners with back/lay ladders; pasted snapshot and last import error
+    let exchange = use_state(|| initial.exchange.clone());
+    let exchange_text = use_state(String::new);
+    let exchange_error = use_state(|| None::<String>);
+    let exchange_reader = use_mut_ref(|| None::<FileReader>);
+
+    // Elo probability model
+    let elo = use_state(|| initial.elo.clone());
+
+    // Poisson / Dixon–Coles goal model; `poisson_pick` is the market sent to the Single Bet
+    let poisson = use_state(|| initial.poisson.clone());
+    let poisson_pick = use_state(|| 0usize);
+
+    // Bet journal, persisted in localStorage
+    let bets = use_state(|| LocalStorage::get::<Vec<BetRecord>>(BETS_KEY).unwrap_or_default());
+    use_effect_with((*bets).clone(), |b| {
+        let _ = LocalStorage::set(BETS_KEY, b);
+    });
+    let bets_reader = use_mut_ref(|| None::<FileReader>);
+    let bets_msg = use_state(|| None::<Result<String, String>>); // outcome of the last JSON import
+
+    // Named presets, persisted in localStorage
+    let presets = use_state(|| LocalStorage::get::<Vec<Preset>>(PRESETS_KEY).unwrap_or_default());
+    use_effect_with((*presets).clone(), |p| {
+        let _ = LocalStorage::set(PRESETS_KEY, p);
+    });
+    let presets_reader = use_mut_ref(|| None::<FileReader>);
+    let preset_name = use_state(String::new);
+    let presets_msg = use_state(|| None::<Result<String, String>>); // outcome of the last JSON import
+    let preset_scope = use_state(|| PresetScope::All);
+    let journal = use_state(|| LocalStorage::get::<JournalSettings>(JOURNAL_KEY).unwrap_or_default());
+    use_effect_with((*journal).clone(), |j| {
+        let _ = LocalStorage::set(JOURNAL_KEY, j);
+    });
+
+    // Journal reporting; current bankroll = start + settled P&L - stakes still open
+    let stats = journal_stats(&bets, journal.start_bank);
+    let current_bank = journal.start_bank + stats.pnl - stats.open_stake;
+    {
+        let bankroll = bankroll.clone();
+        use_effect_with((journal.auto_bankroll, format!("{:.2}", current_bank)), move |(auto, bank)| {
+            if *auto { bankroll.set(bank.clone()); }
+        });
+    }
+
+    // Calibration of your % vs the market % recorded at bet time
+    let decided: Vec<&BetRecord> = bets.iter().filter(|b| matches!(b.status, BetStatus::Won | BetStatus::Lost)).collect();
+    let calib_yours = calibration(&decided.iter().map(|b| (b.prob / 100.0, b.status == BetStatus::Won)).collect::<Vec<_>>());
+    let calib_market = calibration(&decided.iter().map(|b| (b.market_prob.unwrap_or_else(|| b.implied_pct()) / 100.0, b.status == BetStatus::Won)).collect::<Vec<_>>());
+
+    // Closing line value, overall and by market / book
+    let clv_rows: Vec<(&BetRecord, f64, f64)> = bets.iter().filter_map(|b| bet_clv(b, journal.devig).map(|(o, p)| (b, o, p))).collect();
+    let clv_avg = |rows: &[(&BetRecord, f64, f64)]| {
+        let n = rows.len().max(1) as f64;
+        (rows.iter().map(|r| r.1).sum::<f64>() / n, rows.iter().map(|r| r.2).sum::<f64>() / n)
+    };
+    let (clv_odds_avg, clv_prob_avg) = clv_avg(&clv_rows);
+    let clv_breakdown = |key: fn(&BetRecord) -> String| {
+        let mut by: BTreeMap<String, Vec<(&BetRecord, f64, f64)>> = BTreeMap::new();
+        for r in clv_rows.iter() {
+            let k = key(r.0);
+            by.entry(if k.trim().is_empty() { "—".into() } else { k }).or_default().push(*r);
+        }
+        by.into_iter().map(|(k, rows)| { let (o, p) = clv_avg(&rows); (k, rows.len(), o, p) }).collect::<Vec<_>>()
+    };
+    let clv_by_market = clv_breakdown(|b| b.market.clone());
+    let clv_by_book = clv_breakdown(|b| b.book.clone());
 
     // Helpers
     let bankroll_val = || bankroll.trim().replace(',', "").parse::<f64>().unwrap_or(0.0);
@@ -68,12 +706,7 @@ fn app() -> Html {
     let decimal_odds = {
         let s_current = (*odds_input).clone();
         let s = s_current.trim();
-        let parsed = match *odds_format {
-            OddsFormat::Decimal => s.parse::<f64>().ok(),
-            OddsFormat::American => parse_american(s).map(|d| d),
-            OddsFormat::Fractional => parse_fractional(s).map(|d| d),
-        };
-        match parsed {
+        match odds_format.parse(s) {
             Some(d) => Some(d),
             None => {
                 let pm = (*market_prob / 100.0).clamp(1e-9, 1.0 - 1e-9);
@@ -83,42 +716,81 @@ fn app() -> Html {
         }
     };
 
+    // Boosted price (None when no valid boost is entered) and its cap as a fraction of bankroll
+    let boosted_odds = decimal_odds.and_then(|d| parse_boost(&boost_input, d));
+    let boost_cap_frac = cap_fraction(&boost_cap, bankroll_val());
+
     // Computations
-    let (kelly_f, full_bet, half_bet, quarter_bet, ev_per_unit, implied_prob, edge_prob) =
+    let (kelly_f, full_bet, half_bet, quarter_bet, ev_per_unit, implied_prob, edge_prob, b_effective) =
         if let Some(d) = decimal_odds {
             if d <= 1.0 {
-                (0.0, 0.0, 0.0, 0.0, f64::NAN, f64::NAN, f64::NAN)
+                (0.0, 0.0, 0.0, 0.0, f64::NAN, f64::NAN, f64::NAN, f64::NAN)
             } else {
-                // Interpret your input as the probability of the SELECTED side (Yes/No).
-                let p = *your_prob as f64 / 100.0;
-                let b = d - 1.0; // net profit per 1 staked
-                let q = 1.0 - p;
-                let f = ((b * p) - q) / b; // Kelly fraction
-                let f = f.clamp(0.0, 1.0);
+                // Interpret your input as the probability of the SELECTED side (Yes/No) winning;
+                // a push returns the stake, the rest loses.
+                let p = *your_prob / 100.0;
+                let q = (1.0 - p - *push_prob / 100.0).max(0.0);
+                let db = boosted_odds.unwrap_or(d);
+                let f = kelly_boosted(p, q, d, db, boost_cap_frac); // Kelly fraction
+                let b = boosted_b(f, d, db, boost_cap_frac); // net profit per 1 staked at that stake
                 let bank = bankroll_val();
                 let ev = (p * b) - q; // EV per 1 staked
-                let imp = 1.0 / d; // implied prob of the side being backed
-                let edgep = p - imp; // your edge on the backed side
-                (f, bank * f, bank * (f * 0.5), bank * (f * 0.25), ev, imp, edgep)
+                let imp = 1.0 / (1.0 + b); // implied prob of the side being backed
+                let edgep = p / (p + q).max(1e-12) - imp; // your edge on the backed side (pushes excluded)
+                (f, bank * f, bank * (f * 0.5), bank * (f * 0.25), ev, imp, edgep, b)
             }
         } else {
-            (0.0, 0.0, 0.0, 0.0, f64::NAN, f64::NAN, f64::NAN)
+            (0.0, 0.0, 0.0, 0.0, f64::NAN, f64::NAN, f64::NAN, f64::NAN)
         };
 
     // Per-$1 and fair odds metrics for the selected side
-    let p_selected = *your_prob as f64 / 100.0;
-    let d_selected = decimal_odds.unwrap_or(f64::NAN);
-    let b_selected = if d_selected > 1.0 { d_selected - 1.0 } else { f64::NAN };
+    let p_selected = *your_prob / 100.0;
+    let q_selected = (1.0 - p_selected - *push_prob / 100.0).max(0.0);
+    let b_selected = b_effective;
     let win_per_1 = b_selected; // profit if win per $1 staked
-    let loss_per_1 = if d_selected.is_nan() { f64::NAN } else { 1.0 };
-    let fair_decimal = if p_selected > 0.0 { 1.0 / p_selected } else { f64::INFINITY };
+    let loss_per_1 = if b_selected.is_nan() { f64::NAN } else { 1.0 };
+    let fair_decimal = if p_selected > 0.0 { (p_selected + q_selected) / p_selected } else { f64::INFINITY };
     let fair_dec_str = if fair_decimal.is_finite() { format_decimal(fair_decimal) } else { "—".into() };
     let fair_am_str = if fair_decimal.is_finite() { format_american(fair_decimal) } else { "—".into() };
     let fair_fr_str = if fair_decimal.is_finite() { format_fractional(fair_decimal) } else { "—".into() };
     let g_full = if kelly_f > 0.0 && b_selected.is_finite() {
-        let p = p_selected; let f = kelly_f; let b = b_selected;
-        p * (1.0 + f*b).ln() + (1.0 - p) * (1.0 - f).ln()
+        let p = p_selected; let q = q_selected; let f = kelly_f; let b = b_selected;
+        p * (1.0 + f*b).ln() + q * (1.0 - f).ln()
     } else { 0.0 };
+    // Same bet with pushes counted as losses, to show what the push probability is worth
+    let kelly_no_push = match decimal_odds {
+        Some(d) if d > 1.0 => kelly_boosted(p_selected, 1.0 - p_selected, d, boosted_odds.unwrap_or(d), boost_cap_frac),
+        _ => 0.0,
+    };
+    let push_valid = *your_prob + *push_prob <= 100.0 + 1e-9;
+
+    // Portfolio Kelly with the open position: scenarios are selected side wins / push / loses,
+    // bets are more on the selected side at the market odds or a hedge on the other side.
+    let position_s = position_stake.trim().replace(',', "").parse::<f64>().unwrap_or(0.0).max(0.0);
+    let position_d = parse_any(&position_odds).filter(|d| *d > 1.0);
+    let hedge_d = parse_any(&hedge_odds).filter(|d| *d > 1.0).or_else(|| decimal_odds.map(complement_decimal));
+    let position_calc = match (position_d, decimal_odds, hedge_d) {
+        (Some(d0), Some(d), Some(dh)) if position_s > 0.0 && d > 1.0 && bankroll_val() > 0.0 => {
+            let bank = bankroll_val();
+            let same_side = *position_side == *bet_side;
+            let payout = if same_side { [position_s * d0, position_s, 0.0] } else { [0.0, position_s, position_s * d0] };
+            let base: Vec<f64> = payout.iter().map(|x| 1.0 + x / bank).collect();
+            let probs = [p_selected, (*push_prob / 100.0).min(1.0 - p_selected).max(0.0), q_selected];
+            let r = vec![vec![d, 0.0], vec![1.0, 1.0], vec![0.0, dh]];
+            let f = kelly_portfolio(&probs, &base, &r, 1.0);
+            let (more, hedge) = (f.first().cloned().unwrap_or(0.0) * bank, f.get(1).cloned().unwrap_or(0.0) * bank);
+            let after = |i: usize| bank - more - hedge + payout[i] + more * r[i][0] + hedge * r[i][1];
+            Some((more, hedge, dh, [bank + payout[0], bank + payout[2]], [after(0), after(2)]))
+        }
+        _ => None,
+    };
+    let boost_hint = match (decimal_odds, boosted_odds) {
+        (Some(d), Some(db)) => {
+            let cap = if boost_cap_frac.is_finite() { format!("up to ${:.0}", boost_cap_frac * bankroll_val()) } else { "no cap".into() };
+            format!("Boosted to {:.3} ({}), normal {:.3} beyond", db, cap, d)
+        }
+        _ => if boost_input.trim().is_empty() { "No boost".into() } else { "Boost must beat the market odds".into() },
+    };
 
     // Handlers
     let on_market_prob_input = {
@@ -145,6 +817,14 @@ fn app() -> Html {
             your_prob.set(v);
         })
     };
+    let on_push_prob_input = {
+        let push_prob = push_prob.clone();
+        Callback::from(move |e: InputEvent| {
+            let target: HtmlInputElement = e.target_unchecked_into();
+            let v = target.value().parse::<f64>().unwrap_or(0.0).clamp(0.0, 100.0);
+            push_prob.set(v);
+        })
+    };
     let on_odds_format_change = {
         let odds_format = odds_format.clone();
         let odds_input = odds_input.clone();
@@ -171,6 +851,48 @@ fn app() -> Html {
             odds_input.set(target.value());
         })
     };
+    let on_boost_input = {
+        let boost_input = boost_input.clone();
+        Callback::from(move |e: InputEvent| {
+            let target: HtmlInputElement = e.target_unchecked_into();
+            boost_input.set(target.value());
+        })
+    };
+    let on_boost_cap_input = {
+        let boost_cap = boost_cap.clone();
+        Callback::from(move |e: InputEvent| {
+            let target: HtmlInputElement = e.target_unchecked_into();
+            boost_cap.set(target.value());
+        })
+    };
+    let on_position_stake_input = {
+        let position_stake = position_stake.clone();
+        Callback::from(move |e: InputEvent| {
+            let target: HtmlInputElement = e.target_unchecked_into();
+            position_stake.set(target.value());
+        })
+    };
+    let on_position_odds_input = {
+        let position_odds = position_odds.clone();
+        Callback::from(move |e: InputEvent| {
+            let target: HtmlInputElement = e.target_unchecked_into();
+            position_odds.set(target.value());
+        })
+    };
+    let on_position_side_change = {
+        let position_side = position_side.clone();
+        Callback::from(move |e: Event| {
+            let target: HtmlSelectElement = e.target_unchecked_into();
+            position_side.set(if target.selected_index() > 0 { BetSide::OnOpposite } else { BetSide::OnEvent });
+        })
+    };
+    let on_hedge_odds_input = {
+        let hedge_odds = hedge_odds.clone();
+        Callback::from(move |e: InputEvent| {
+            let target: HtmlInputElement = e.target_unchecked_into();
+            hedge_odds.set(target.value());
+        })
+    };
     let on_bankroll_input = {
         let bankroll = bankroll.clone();
         Callback::from(move |e: InputEvent| {
@@ -184,6 +906,7 @@ fn app() -> Html {
         let odds_input = odds_input.clone();
         let odds_format = odds_format.clone();
         let your_prob_state = your_prob.clone();
+        let push_prob = push_prob.clone();
         Callback::from(move |e: Event| {
             let target: HtmlSelectElement = e.target_unchecked_into();
             let idx = target.selected_index();
@@ -201,9 +924,9 @@ fn app() -> Html {
                     odds_input.set(formatted);
                 }
             }
-            // Since "Your %" refers to the SELECTED side, mirror it when toggling
+            // Since "Your %" refers to the SELECTED side, mirror it when toggling (pushes stay pushes)
             let cur = *your_prob_state;
-            your_prob_state.set(100.0 - cur);
+            your_prob_state.set((100.0 - cur - *push_prob).max(0.0));
             bet_side.set(side);
         })
     };
@@ -234,6 +957,188 @@ fn app() -> Html {
     let total_mkt: f64 = multi_rows.iter().map(|(r, _, _)| r.mkt).sum();
     let warn_market_sum = (total_mkt - 100.0).abs() > 0.5;
 
+    // Current inputs, shareable as a link
+    let app_state = AppState {
+        market_prob: *market_prob,
+        your_prob: *your_prob,
+        push_prob: *push_prob,
+        position_stake: (*position_stake).clone(),
+        position_odds: (*position_odds).clone(),
+        position_side: *position_side,
+        hedge_odds: (*hedge_odds).clone(),
+        bet_side: *bet_side,
+        odds_format: *odds_format,
+        odds_input: (*odds_input).clone(),
+        bankroll: (*bankroll).clone(),
+        boost_input: (*boost_input).clone(),
+        boost_cap: (*boost_cap).clone(),
+        outcomes: (*outcomes).clone(),
+        compares: (*compares).clone(),
+        three: (*three).clone(),
+        arb_stake: (*arb_stake).clone(),
+        correlations: (*correlations).clone(),
+        parlay_adj: *parlay_adj,
+        cashout: (*cashout).clone(),
+        each_way: (*each_way).clone(),
+        asian: (*asian).clone(),
+        exchange: (*exchange).clone(),
+        live_url: (*live_url).clone(),
+        single_live_id: (*single_live_id).clone(),
+        elo: (*elo).clone(),
+        poisson: (*poisson).clone(),
+    };
+    use_effect_with(app_state.clone(), save_state);
+
+    // Load a full set of inputs into every panel
+    let apply_state = {
+        let (market_prob, your_prob, push_prob) = (market_prob.clone(), your_prob.clone(), push_prob.clone());
+        let (position_stake, position_odds, position_side, hedge_odds) = (position_stake.clone(), position_odds.clone(), position_side.clone(), hedge_odds.clone());
+        let (bet_side, odds_format, odds_input, bankroll) = (bet_side.clone(), odds_format.clone(), odds_input.clone(), bankroll.clone());
+        let (boost_input, boost_cap) = (boost_input.clone(), boost_cap.clone());
+        let (outcomes, compares, three) = (outcomes.clone(), compares.clone(), three.clone());
+        let (arb_stake, correlations, parlay_adj) = (arb_stake.clone(), correlations.clone(), parlay_adj.clone());
+        let (cashout, each_way, asian, exchange) = (cashout.clone(), each_way.clone(), asian.clone(), exchange.clone());
+        let (live_url, single_live_id, elo, poisson) = (live_url.clone(), single_live_id.clone(), elo.clone(), poisson.clone());
+        Callback::from(move |s: AppState| {
+            let s = s.validated();
+            market_prob.set(s.market_prob);
+            your_prob.set(s.your_prob);
+            push_prob.set(s.push_prob);
+            position_stake.set(s.position_stake);
+            position_odds.set(s.position_odds);
+            position_side.set(s.position_side);
+            hedge_odds.set(s.hedge_odds);
+            bet_side.set(s.bet_side);
+            odds_format.set(s.odds_format);
+            odds_input.set(s.odds_input);
+            bankroll.set(s.bankroll);
+            boost_input.set(s.boost_input);
+            boost_cap.set(s.boost_cap);
+            outcomes.set(s.outcomes);
+            compares.set(s.compares);
+            three.set(s.three);
+            arb_stake.set(s.arb_stake);
+            correlations.set(s.correlations);
+            parlay_adj.set(s.parlay_adj);
+            cashout.set(s.cashout);
+            each_way.set(s.each_way);
+            asian.set(s.asian);
+            exchange.set(s.exchange);
+            live_url.set(s.live_url);
+            single_live_id.set(s.single_live_id);
+            elo.set(s.elo);
+            poisson.set(s.poisson);
+        })
+    };
+    let on_reset = {
+        let apply_state = apply_state.clone();
+        Callback::from(move |_| {
+            let confirmed = gloo::utils::window().confirm_with_message("Reset every panel to its default inputs? The bet journal is kept.").unwrap_or(false);
+            if confirmed { apply_state.emit(AppState::default()); }
+        })
+    };
+    // Live odds: apply queued price updates to Compare rows and the single bet, then clear the queue
+    {
+        let (compares, odds_input, live) = (compares.clone(), odds_input.clone(), live.clone());
+        let (live_shown, live_before) = (live_shown.clone(), live_before.clone());
+        let (single_id, format) = (single_live_id.trim().to_string(), *odds_format);
+        use_effect_with(live.seq, move |_| {
+            if !live.pending.is_empty() {
+                {
+                    let shown = live_shown.borrow();
+                    let mut before = live_before.borrow_mut();
+                    before.retain(|id, _| live.moved.contains_key(id));
+                    for u in live.pending.iter() {
+                        before.insert(u.id.clone(), shown.get(&u.id).cloned().unwrap_or_default());
+                    }
+                }
+                let mut rows = (*compares).clone();
+                let mut changed = false;
+                for u in live.pending.iter() {
+                    for r in rows.iter_mut().filter(|r| !r.id.trim().is_empty() && r.id.trim() == u.id) {
+                        r.odds = format_price(u.odds);
+                        changed = true;
+                    }
+                    if !single_id.is_empty() && single_id == u.id {
+                        odds_input.set(match format {
+                            OddsFormat::Decimal => format_price(u.odds),
+                            OddsFormat::American => format_american(u.odds),
+                            OddsFormat::Fractional => format_fractional(u.odds),
+                        });
+                    }
+                }
+                if changed { compares.set(rows); }
+                live.dispatch(LiveAction::Applied);
+            }
+        });
+    }
+    // Subscribe to every feed id in use, again whenever the set changes
+    let live_ids: Vec<String> = {
+        let mut v: Vec<String> = compares.iter().map(|r| r.id.trim().to_string())
+            .chain(std::iter::once(single_live_id.trim().to_string()))
+            .filter(|id| !id.is_empty())
+            .collect();
+        v.sort();
+        v.dedup();
+        v
+    };
+    {
+        let live_socket = live_socket.clone();
+        use_effect_with((live_ids.clone(), live.status == LiveStatus::Live), move |(ids, open)| {
+            if *open {
+                if let Some(s) = live_socket.borrow().as_ref() { live_subscribe(&s.ws, ids); }
+            }
+        });
+    }
+    let live_active = matches!(live.status, LiveStatus::Connecting | LiveStatus::Live);
+    let on_live_toggle = {
+        let (live_socket, dispatcher, url) = (live_socket.clone(), live.dispatcher(), live_url.trim().to_string());
+        Callback::from(move |_| {
+            let old = live_socket.borrow_mut().take();
+            drop(old);
+            if live_active {
+                dispatcher.dispatch(LiveAction::Status(LiveStatus::Off));
+                return;
+            }
+            match live_connect(&url, dispatcher.clone()) {
+                Ok(s) => {
+                    *live_socket.borrow_mut() = Some(s);
+                    dispatcher.dispatch(LiveAction::Status(LiveStatus::Connecting));
+                }
+                Err(e) => dispatcher.dispatch(LiveAction::Status(LiveStatus::Closed(e))),
+            }
+        })
+    };
+    let link_copied = use_state(|| false);
+    let on_copy_link = {
+        let app_state = app_state.clone();
+        let link_copied = link_copied.clone();
+        Callback::from(move |_| {
+            if let Some(url) = share_link(&app_state) {
+                let _ = gloo::utils::window().navigator().clipboard().write_text(&url);
+                link_copied.set(true);
+                let link_copied = link_copied.clone();
+                gloo::timers::callback::Timeout::new(2000, move || link_copied.set(false)).forget();
+            }
+        })
+    };
+
+    // Record a recommendation in the bet journal
+    let on_place = {
+        let bets = bets.clone();
+        Callback::from(move |rec: BetRecord| {
+            let mut v = (*bets).clone();
+            add_bet(&mut v, rec, js_sys::Date::now());
+            bets.set(v);
+        })
+    };
+    let place_button = |rec: BetRecord| {
+        let on_place = on_place.clone();
+        let disabled = rec.stake.is_nan() || rec.stake <= 0.0;
+        let onclick = Callback::from(move |_| on_place.emit(rec.clone()));
+        html!{ <button class="secondary small" style="float:right;" {onclick} {disabled}>{"Place"}</button> }
+    };
+
     // Add-outcome handler
     let on_add_outcome = {
         let outcomes = outcomes.clone();
@@ -249,7 +1154,7 @@ fn app() -> Html {
         let compares = compares.clone();
         Callback::from(move |_| {
             let mut v = (*compares).clone();
-            v.push(CompareRow{ name: format!("Selection {}", v.len()+1), group: "Market".into(), odds: "".into(), your: 50.0 });
+            v.push(CompareRow{ name: format!("Selection {}", v.len()+1), group: "Market".into(), your: 50.0, ..Default::default() });
             compares.set(v);
         })
     };
@@ -266,30 +1171,139 @@ fn app() -> Html {
     // Side labels and complementary odds for clarity in UI
     let selected_side_label = match *bet_side { BetSide::OnEvent => "Yes", BetSide::OnOpposite => "No" };
     let other_side_label = match *bet_side { BetSide::OnEvent => "No", BetSide::OnOpposite => "Yes" };
-    let comp_dec_odds = decimal_odds.map(|d| complement_decimal(d));
+    let comp_dec_odds = decimal_odds.map(complement_decimal);
 
     // Compare panel computations: build grouped view data outside html!
     let cmp_rows = (*compares).clone();
-    let mut cmp_by_group: HashMap<String, Vec<(usize, f64, f64, f64, f64)>> = HashMap::new();
+    let bank_for_cmp = bankroll_val();
+    // Line shopping: rows with the same group and name are one selection priced at several books
+    let mut quotes_by_sel: HashMap<(String, String), Vec<(usize, f64)>> = HashMap::new();
     for (idx, r) in cmp_rows.iter().enumerate() {
-        if let Some(d) = parse_any(&r.odds) {
-            if d > 1.0 {
-                let p = (r.your/100.0).clamp(0.0, 1.0);
-                let imp = 1.0/d;
-                let b = d - 1.0; let q = 1.0 - p;
-                let f = (((b*p) - q) / b).clamp(0.0, 1.0);
-                let ev = (p*b) - q;
-                cmp_by_group.entry(r.group.clone()).or_default().push((idx, d, f, imp, ev));
-            }
+        if let Some(d) = parse_any(&r.odds).filter(|d| *d > 1.0) {
+            quotes_by_sel.entry((r.group.clone(), r.name.trim().to_string())).or_default().push((idx, d));
         }
     }
-    let mut compare_view: Vec<(String, f64, f64, Vec<(usize, f64, f64, f64, f64)>)> = Vec::new();
+    let mut cmp_by_group: HashMap<String, Vec<CompareCalc>> = HashMap::new();
+    for ((group, _), quotes) in quotes_by_sel.into_iter() {
+        // Kelly is sized at the best quote only, with each book's boost applied before choosing
+        let terms: Vec<(f64, f64, f64, f64)> = quotes.iter().map(|(i, d)| {
+            let r = &cmp_rows[*i];
+            ((r.your/100.0).clamp(0.0, 1.0), *d, parse_boost(&r.boost, *d).unwrap_or(*d), cap_fraction(&r.boost_cap, bank_for_cmp))
+        }).collect();
+        let (idx, d) = quotes[best_quote(&terms)];
+        let avg = quotes.iter().map(|q| q.1).sum::<f64>() / quotes.len() as f64;
+        let others = quotes.into_iter().filter(|q| q.0 != idx).collect();
+        let r = &cmp_rows[idx];
+        let p = (r.your/100.0).clamp(0.0, 1.0);
+        let d_boost = parse_boost(&r.boost, d).unwrap_or(d);
+        let cap = cap_fraction(&r.boost_cap, bank_for_cmp);
+        let f = kelly_boosted(p, 1.0 - p, d, d_boost, cap);
+        let b = boosted_b(f, d, d_boost, cap); let q = 1.0 - p;
+        let ev = (p*b) - q;
+        cmp_by_group.entry(group).or_default().push(CompareCalc { idx, d, d_boost, cap, f, ev, avg, others });
+    }
+    for items in cmp_by_group.values_mut() { items.sort_by_key(|c| c.idx); }
+    let mut compare_view: Vec<(String, f64, f64, Vec<CompareCalc>)> = Vec::new();
     for (g, items) in cmp_by_group.into_iter() {
-        let sum_f: f64 = items.iter().map(|(_,_,f,_,_)| *f).sum();
+        let sum_f: f64 = items.iter().map(|c| c.f).sum();
         let scale = if sum_f > 1.0 { 1.0/sum_f } else { 1.0 };
         compare_view.push((g, sum_f, scale, items));
     }
-    let bank_for_cmp = bankroll_val();
+
+    // Live highlights: only ids whose move changed a recommendation
+    let mut shown_recs = LiveRecs::new();
+    for (_, _, scale, items) in compare_view.iter() {
+        for c in items.iter() {
+            for i in std::iter::once(c.idx).chain(c.others.iter().map(|o| o.0)) {
+                let id = cmp_rows[i].id.trim();
+                if !id.is_empty() { shown_recs.entry(id.to_string()).or_default().push((c.idx, c.f * scale)); }
+            }
+        }
+    }
+    if !single_live_id.trim().is_empty() {
+        shown_recs.entry(single_live_id.trim().to_string()).or_default().push((usize::MAX, kelly_f));
+    }
+    let live_changed = live_changed(&live.moved, &live_before.borrow(), &shown_recs);
+    *live_shown.borrow_mut() = shown_recs;
+    let single_moved = live.moved.get(single_live_id.trim()).filter(|_| live_changed.iter().any(|id| id == single_live_id.trim())).cloned();
+
+    // Sure bets and middles: best price per selection within each group
+    let mut sure_bets: Vec<SureBet> = Vec::new();
+    let mut middles: Vec<Middle> = Vec::new();
+    for (g, _, _, items) in compare_view.iter() {
+        let best = best_prices(items, &cmp_rows);
+        let book: f64 = best.values().map(|(_, d)| 1.0 / d).sum();
+        if best.len() >= 2 && book < 1.0 {
+            let legs = best.values().map(|(i, d)| (*i, *d, (1.0 / d) / book)).collect();
+            sure_bets.push(SureBet { group: g.clone(), legs, book });
+        }
+        let lines: Vec<(usize, f64, LineKind, String, f64)> = best.values()
+            .filter_map(|(i, d)| parse_line(&cmp_rows[*i].name).map(|(kind, team, line)| (*i, *d, kind, team, line)))
+            .collect();
+        for (a, da, ka, ta, la) in lines.iter() {
+            for (b, db, kb, tb, lb) in lines.iter() {
+                // Over X + Under Y with Y > X; spreads on different teams with lines summing above zero
+                let window = match (ka, kb) {
+                    (LineKind::Over, LineKind::Under) if lb > la => Some((*la, *lb)),
+                    (LineKind::Spread, LineKind::Spread) if a < b && ta != tb && la + lb > 0.0 => Some((-la, *lb)),
+                    _ => None,
+                };
+                if let Some((lo, hi)) = window {
+                    let book = 1.0 / da + 1.0 / db;
+                    let shares = ((1.0 / da) / book, (1.0 / db) / book);
+                    let hits = middle_hits(lo, hi, *da, *db);
+                    if !hits.is_empty() {
+                        middles.push(Middle { group: g.clone(), a: *a, b: *b, shares, lo, hi, miss: 1.0 / book, hits });
+                    }
+                }
+            }
+        }
+    }
+    let arb_total = arb_stake.trim().replace(',', "").parse::<f64>().unwrap_or(0.0).max(0.0);
+
+    // Correlated allocation: joint Kelly over the best price of every Compare selection, on draws from a Gaussian
+    // copula (one categorical leg per group). Memoized: the draws and the solver are too slow for every keystroke.
+    let mut corr_legs: Vec<(usize, CorrLeg)> = compare_view.iter()
+        .flat_map(|(_, _, _, items)| items.iter().map(|c| {
+            let r = &cmp_rows[c.idx];
+            (c.idx, CorrLeg { group: r.group.clone(), name: r.name.clone(), d: c.d, p: (r.your/100.0).clamp(0.0, 1.0) })
+        }))
+        .collect();
+    corr_legs.sort_by_key(|l| l.0);
+    let corr_calc = use_memo((corr_legs.iter().map(|l| l.1.clone()).collect::<Vec<_>>(), (*correlations).clone()), |(legs, corr)| {
+        (!corr.is_empty() && legs.len() >= 2 && legs.len() <= COPULA_MAX_LEGS).then(|| correlated_kelly(legs, corr))
+    });
+
+    // Parlay: legs are the Compare rows marked for the parlay
+    let parlay_legs: Vec<(usize, f64, f64)> = cmp_rows.iter().enumerate()
+        .filter(|(_, r)| r.parlay)
+        .filter_map(|(i, r)| parse_any(&r.odds).filter(|d| *d > 1.0).map(|d| (i, d, (r.your/100.0).clamp(0.0, 1.0))))
+        .collect();
+    // Two outcomes of one Compare group can't both win, so they are not parlay legs
+    let parlay_conflict: Option<(usize, usize)> = parlay_legs.iter().enumerate()
+        .flat_map(|(a, la)| parlay_legs[a + 1..].iter().map(move |lb| (la.0, lb.0)))
+        .find(|(a, b)| !cmp_rows[*a].group.trim().is_empty() && cmp_rows[*a].group.trim() == cmp_rows[*b].group.trim());
+    let parlay_ok = parlay_legs.len() >= 2 && parlay_legs.len() <= PARLAY_MAX_LEGS && parlay_conflict.is_none();
+    let parlay_d: f64 = parlay_legs.iter().map(|l| l.1).product();
+    let parlay_p_indep: f64 = parlay_legs.iter().map(|l| l.2).product();
+    // One joint distribution over every win/lose combination of the legs prices both the parlay and the singles
+    let parlay_probs = if parlay_ok {
+        parlay_joint(&parlay_legs.iter().map(|l| l.2).collect::<Vec<_>>(), *parlay_adj / 100.0)
+    } else { vec![] };
+    let parlay_p = parlay_probs.last().cloned().unwrap_or(0.0);
+    let parlay_ev = parlay_p * parlay_d - 1.0;
+    let parlay_f = if parlay_ok { ((parlay_p * parlay_d - 1.0) / (parlay_d - 1.0)).clamp(0.0, 1.0) } else { 0.0 };
+    let parlay_g = log_growth(&[parlay_p, 1.0 - parlay_p], &[vec![parlay_d], vec![0.0]], &[parlay_f]);
+    // Singles staked simultaneously: exact Kelly over the same joint
+    let (singles_f, singles_g) = if parlay_ok {
+        let returns: Vec<Vec<f64>> = (0..parlay_probs.len())
+            .map(|mask| parlay_legs.iter().enumerate().map(|(j, l)| if mask & (1 << j) != 0 { l.1 } else { 0.0 }).collect())
+            .collect();
+        let f = kelly_scenarios(&parlay_probs, &returns, 1.0);
+        let g = log_growth(&parlay_probs, &returns, &f);
+        (f, g)
+    } else { (vec![], 0.0) };
+    let singles_sum: f64 = singles_f.iter().sum();
 
     // Three-way exact Kelly compute
     let three_rows = (*three).clone();
@@ -305,29 +1319,156 @@ fn app() -> Html {
     let three_alloc = kelly_multi_exact(&p_vec, &d_vec, 1.0);
     let three_sum: f64 = three_alloc.iter().sum();
 
-    html! {
-        <div class="container">
-            <header>
-                <h1>{"EdgeRunner"}</h1>
-                <div class="tooltip pill" data-tooltip="Professional Kelly Criterion calculator for optimal bet sizing">
-                    {"Kelly Calculator"}
-                </div>
-            </header>
+    // Asian handicap: settle every goal difference, then group into win/half-win/push/half-loss/loss
+    let ah = (*asian).clone();
+    let ah_line = parse_ah_line(&ah.line);
+    let ah_d = parse_any(&ah.odds).filter(|d| *d > 1.0);
+    let ah_total: f64 = ah.dist.iter().sum();
+    let ah_outcomes: Vec<(&str, f64, f64)> = match (ah_line, ah_d) {
+        (Some(line), Some(d)) if ah_total > 0.0 => {
+            let mut buckets = vec![
+                ("Win", 0.0, d), ("Half win", 0.0, (1.0 + d) / 2.0), ("Push", 0.0, 1.0),
+                ("Half loss", 0.0, 0.5), ("Loss", 0.0, 0.0),
+            ];
+            for (k, pct) in ah.dist.iter().enumerate() {
+                let gd = (AH_MIN_GD + k as i32) as f64;
+                let ret = asian_return(if ah.away { -gd } else { gd }, line, d);
+                if let Some(b) = buckets.iter_mut().find(|b| (b.2 - ret).abs() < 1e-9) { b.1 += pct / ah_total; }
+            }
+            buckets
+        }
+        _ => vec![],
+    };
+    let (ah_frac, ah_ev) = if ah_outcomes.is_empty() { (0.0, f64::NAN) } else {
+        let p: Vec<f64> = ah_outcomes.iter().map(|o| o.1).collect();
+        let r: Vec<Vec<f64>> = ah_outcomes.iter().map(|o| vec![o.2]).collect();
+        let f = kelly_scenarios(&p, &r, 1.0).first().cloned().unwrap_or(0.0);
+        (f, ah_outcomes.iter().map(|o| o.1 * o.2).sum::<f64>() - 1.0)
+    };
 
-            <div class="grid">
-                <div class="card">
-                    <h2>
-                        <span>{"Single Bet Inputs"}</span>
-                        { if !bankroll_valid || !odds_valid {
-                            html!{ <span class="status-indicator warning">{"Check inputs"}</span> }
-                        } else {
-                            html!{ <span class="status-indicator success">{"Valid"}</span> }
-                        }}
-                    </h2>
-                    
-                    <div class="input-group">
-                        <label class="tooltip" data-tooltip="Market's implied probability that the event will occur">
-                            {"Market Probability (%)"}
+    // Cash-out: fraction of the bet to cash out that maximizes expected log bankroll
+    let co = (*cashout).clone();
+    let co_stake = co.stake.trim().replace(',', "").parse::<f64>().ok().filter(|v| *v > 0.0);
+    let co_d = parse_any(&co.odds).filter(|d| *d > 1.0);
+    let co_offer = co.offer.trim().replace(',', "").parse::<f64>().ok().filter(|v| *v >= 0.0);
+    let co_calc = match (co_stake, co_d, co_offer) {
+        (Some(stake), Some(d), Some(offer)) if bankroll_val() > 0.0 => {
+            let bank = bankroll_val();
+            let p = (co.prob / 100.0).clamp(0.0, 1.0);
+            Some(cashout_eval(bank, stake * d, offer, p))
+        }
+        _ => None,
+    };
+
+    // Each-way: one combined bet over {win, place-only, lose}, half the stake on each part
+    let ew = (*each_way).clone();
+    let ew_win_d = parse_any(&ew.odds).filter(|d| *d > 1.0);
+    let ew_place_d = ew_win_d.map(|d| place_decimal(d, ew.terms));
+    let ew_pw = (ew.win / 100.0).clamp(0.0, 1.0);
+    let ew_pp = ((ew.place - ew.win) / 100.0).clamp(0.0, 1.0 - ew_pw); // place-only
+    let ew_pl = 1.0 - ew_pw - ew_pp;
+    let ew_valid = ew.place >= ew.win;
+    let (ew_frac, ew_ev) = match (ew_win_d, ew_place_d) {
+        (Some(dw), Some(dp)) => {
+            let returns = [(dw + dp) / 2.0, dp / 2.0, 0.0]; // gross return per 1 of total stake
+            let r: Vec<Vec<f64>> = returns.iter().map(|x| vec![*x]).collect();
+            let f = kelly_scenarios(&[ew_pw, ew_pp, ew_pl], &r, 1.0).first().cloned().unwrap_or(0.0);
+            (f, ew_pw * returns[0] + ew_pp * returns[1] - 1.0)
+        }
+        _ => (0.0, f64::NAN),
+    };
+
+    // Exchange: back and lay at the best prices, each stake capped by the size available there
+    let ex = (*exchange).clone();
+    let ex_calc = exchange_kelly(&ex, bankroll_val());
+    let ex_your_sum: f64 = ex.runners.iter().map(|r| r.yours).sum();
+
+    // Every panel's sized bets, for export
+    let mut recommendations: Vec<Recommendation> = Vec::new();
+    if full_bet > 0.0 {
+        let market = if boosted_odds.is_some() { "Single (boosted)" } else { "Single" };
+        recommendations.push(Recommendation::new("Single Bet", selected_side_label.into(), market.into(), String::new(), 1.0 + b_effective, *your_prob, kelly_f, full_bet, ev_per_unit));
+    }
+    if let (Some((more, hedge, dh, _, _)), Some(d)) = (position_calc, decimal_odds) {
+        let bank = bankroll_val();
+        recommendations.push(Recommendation::new("Open Position", format!("Add on {}", selected_side_label), "Single".into(), String::new(), d, *your_prob, more / bank, more, p_selected * (d - 1.0) - q_selected));
+        recommendations.push(Recommendation::new("Open Position", format!("Hedge on {}", other_side_label), "Single".into(), String::new(), dh, 100.0 * q_selected, hedge / bank, hedge, q_selected * (dh - 1.0) - p_selected));
+    }
+    for (r, d, f) in multi_rows.iter() {
+        let rec = f * multi_scale;
+        recommendations.push(Recommendation::new("Multiple Outcomes", r.name.clone(), String::new(), String::new(), *d, r.yours, rec, bankroll_val() * rec, r.yours / 100.0 * d - 1.0));
+    }
+    let mut compare_sorted: Vec<&(String, f64, f64, Vec<CompareCalc>)> = compare_view.iter().collect();
+    compare_sorted.sort_by(|a, b| a.0.cmp(&b.0));
+    for (_, _, scale, items) in compare_sorted {
+        for c in items.iter() {
+            let r = &cmp_rows[c.idx];
+            let rec = c.f * scale;
+            let b = boosted_b(rec, c.d, c.d_boost, c.cap);
+            let p = (r.your / 100.0).clamp(0.0, 1.0);
+            recommendations.push(Recommendation::new("Compare", r.name.clone(), r.group.clone(), r.book.clone(), 1.0 + b, r.your, rec, bank_for_cmp * rec, p * b - (1.0 - p)));
+        }
+    }
+    if let Some(calc) = corr_calc.as_ref() {
+        for ((idx, leg), f) in corr_legs.iter().zip(calc.correlated.iter()) {
+            let r = &cmp_rows[*idx];
+            recommendations.push(Recommendation::new("Correlated Compare", r.name.clone(), r.group.clone(), r.book.clone(), leg.d, r.your, *f, bank_for_cmp * f, leg.p * leg.d - 1.0));
+        }
+    }
+    if parlay_ok {
+        let selection = parlay_legs.iter().map(|l| cmp_rows[l.0].name.clone()).collect::<Vec<_>>().join(" + ");
+        recommendations.push(Recommendation::new("Parlay", selection, String::new(), String::new(), parlay_d, 100.0 * parlay_p, parlay_f, bank_for_cmp * parlay_f, parlay_ev));
+    }
+    for (i, r) in three_rows.iter().enumerate() {
+        let frac = three_alloc.get(i).cloned().unwrap_or(0.0);
+        recommendations.push(Recommendation::new("Three-Way", r.name.clone(), String::new(), String::new(), d_vec[i], r.yours, frac, bankroll_val() * frac, p_vec[i] * d_vec[i] - 1.0));
+    }
+    if let (Some(_), Some(d)) = (ah_line, ah_d) {
+        let side = format!("{} {}", if ah.away { "Away" } else { "Home" }, ah.line.trim());
+        let p_ahead: f64 = ah_outcomes.iter().filter(|o| o.2 > 1.0).map(|o| o.1).sum(); // win or half win
+        recommendations.push(Recommendation::new("Asian Handicap", side, String::new(), String::new(), d, 100.0 * p_ahead, ah_frac, bankroll_val() * ah_frac, ah_ev));
+    }
+    if let Some(dw) = ew_win_d.filter(|_| ew_valid) { // shown by the Each-Way card only when valid
+        recommendations.push(Recommendation::new("Each-Way", format!("Each-way 1/{} × {} places", ew.terms, ew.places), String::new(), String::new(), dw, ew.win, ew_frac, bankroll_val() * ew_frac, ew_ev));
+    }
+    for (r, c) in ex.runners.iter().zip(ex_calc.iter()) {
+        let [back, lay] = exchange_bets(&ex, r, c);
+        if let Some(b) = back { recommendations.push(exchange_recommendation(&b, c.back_ev)); }
+        if let Some(b) = lay { recommendations.push(exchange_recommendation(&b, c.lay_ev * c.lay_liability / c.lay_stake)); } // EV per 1 of backer's stake
+    }
+    recommendations.retain(|r| r.stake > 0.0);
+
+    html! {
+        <div class="container">
+            <header>
+                <h1>{"EdgeRunner"}</h1>
+                <div style="display:flex; align-items:center; gap:12px;">
+                    <button class="secondary small" onclick={on_copy_link} title="Copy a link that restores these inputs">
+                        { if *link_copied { "Link copied" } else { "Copy link" } }
+                    </button>
+                    <button class="secondary small" onclick={on_reset} title="Restore the default inputs in every panel">
+                        {"Reset to defaults"}
+                    </button>
+                    <div class="tooltip pill" data-tooltip="Professional Kelly Criterion calculator for optimal bet sizing">
+                        {"Kelly Calculator"}
+                    </div>
+                </div>
+            </header>
+
+            <div class="grid">
+                <div class="card">
+                    <h2>
+                        <span>{"Single Bet Inputs"}</span>
+                        { if !bankroll_valid || !odds_valid {
+                            html!{ <span class="status-indicator warning">{"Check inputs"}</span> }
+                        } else {
+                            html!{ <span class="status-indicator success">{"Valid"}</span> }
+                        }}
+                    </h2>
+                    
+                    <div class="input-group">
+                        <label class="tooltip" data-tooltip="Market's implied probability that the event will occur">
+                            {"Market Probability (%)"}
                         </label>
                         <div class="row" style="align-items:center;">
                             <input 
@@ -377,6 +1518,30 @@ fn app() -> Html {
                         </div>
                     </div>
 
+                    <div class="row" style="align-items:end;">
+                        <div class="input-group">
+                            <label class="tooltip" data-tooltip="Boosted odds (any format) or a profit boost like 25%">
+                                {"Boost (Optional)"}
+                            </label>
+                            <input
+                                placeholder={"e.g. 2.50 or 25%"}
+                                value={(*boost_input).clone()}
+                                oninput={on_boost_input}
+                                aria-label="Boost input" />
+                        </div>
+                        <div class="input-group">
+                            <label class="tooltip" data-tooltip="Max stake paid at the boosted price; the rest is paid at normal odds">
+                                {"Boost Max Stake ($)"}
+                            </label>
+                            <input
+                                placeholder={"blank = no cap"}
+                                value={(*boost_cap).clone()}
+                                oninput={on_boost_cap_input}
+                                aria-label="Boost max stake" />
+                        </div>
+                    </div>
+                    <div class="hint" style="margin-top:-8px; margin-bottom:16px;">{boost_hint}</div>
+
                     <div class="section-divider"></div>
 
                     <div class="row" style="align-items:end;">
@@ -404,6 +1569,28 @@ fn app() -> Html {
                         </div>
                     </div>
 
+                    <div class="input-group">
+                        <label class="tooltip" data-tooltip="Chance the bet pushes and the stake is returned (whole-number spreads/totals)">
+                            {"Push Probability (%)"}
+                        </label>
+                        <input
+                            type="number"
+                            min="0"
+                            max="100"
+                            step="0.1"
+                            value={format!("{:.1}", *push_prob)}
+                            oninput={on_push_prob_input}
+                            class={if push_valid { "" } else { "error" }}
+                            aria-label="Push probability" />
+                        <div class="hint">
+                            { if push_valid {
+                                format!("Lose: {:.1}%", 100.0 * q_selected)
+                            } else {
+                                "Your % plus push % must not exceed 100%".to_string()
+                            }}
+                        </div>
+                    </div>
+
                     <div class="input-group">
                         <label class="tooltip" data-tooltip="Your total available betting capital">
                             {"Total Bankroll ($)"}
@@ -423,11 +1610,42 @@ fn app() -> Html {
                             }}
                         </div>
                     </div>
+
+                    <div class="section-divider"></div>
+                    <div class="muted" style="margin-bottom:8px;">{"Current Position (Optional)"}</div>
+                    <div class="row three" style="align-items:end;">
+                        <div class="input-group">
+                            <label class="tooltip" data-tooltip="Stake already placed (not part of the bankroll above)">{"Stake ($)"}</label>
+                            <input placeholder={"e.g. 100"} value={(*position_stake).clone()} oninput={on_position_stake_input} aria-label="Current position stake" />
+                        </div>
+                        <div class="input-group">
+                            <label>{"Odds Taken"}</label>
+                            <input placeholder={"e.g. 2.50, +150"} value={(*position_odds).clone()} oninput={on_position_odds_input}
+                                class={if position_odds.trim().is_empty() || position_d.is_some() { "" } else { "error" }} aria-label="Current position odds" />
+                        </div>
+                        <div class="input-group">
+                            <label>{"On Side"}</label>
+                            <select onchange={on_position_side_change} aria-label="Current position side">
+                                <option selected={matches!(*position_side, BetSide::OnEvent)}>{"Yes"}</option>
+                                <option selected={matches!(*position_side, BetSide::OnOpposite)}>{"No"}</option>
+                            </select>
+                        </div>
+                    </div>
+                    <div class="input-group">
+                        <label class="tooltip" data-tooltip="Current price on the other side; blank uses the no-vig complement">
+                            {format!("Hedge Odds — {} (Optional)", other_side_label)}
+                        </label>
+                        <input placeholder={"blank = no-vig complement"} value={(*hedge_odds).clone()} oninput={on_hedge_odds_input} aria-label="Hedge odds" />
+                    </div>
                 </div>
 
-                <div class="card">
+                <div class="card" style={if single_moved.is_some() { "box-shadow: inset 3px 0 0 var(--warning);" } else { "" }}>
                     <h2>
                         <span>{"Recommendation"}</span>
+                        { match single_moved {
+                            Some((old, new)) => html!{ <span class="status-indicator warning">{format!("Live {:.3} → {:.3}", old, new)}</span> },
+                            None => html!{},
+                        }}
                         <span class={format!("status-indicator {}", kelly_status)}>
                             { match kelly_status {
                                 "success" => "Optimal",
@@ -467,6 +1685,18 @@ fn app() -> Html {
                                 <div class="hint" style="margin-top:12px;">
                                     {"Consider fractional Kelly sizing (Half/Quarter) to reduce volatility"}
                                 </div>
+                                <div style="margin-top:8px; overflow:hidden;">
+                                    { place_button(BetRecord::new(format!("Single bet — {}", selected_side_label), "Single".into(), String::new(), 1.0 + b_effective, *your_prob, full_bet, kelly_f)
+                                        .with_market(match *bet_side { BetSide::OnEvent => *market_prob, BetSide::OnOpposite => 100.0 - *market_prob })) }
+                                </div>
+                                { if *push_prob > 0.0 {
+                                    html!{
+                                        <div class="hint">
+                                            {format!("Push {:.1}%: ${:.0} vs ${:.0} if pushes were losses ({:+.2}% of bankroll)",
+                                                *push_prob, full_bet, bankroll_val()*kelly_no_push, 100.0*(kelly_f - kelly_no_push))}
+                                        </div>
+                                    }
+                                } else { html!{} }}
                             </>
                         }
                     } else {
@@ -476,6 +1706,33 @@ fn app() -> Html {
                             </div>
                         }
                     }}
+                    { if let Some((more, hedge, dh, before, after)) = position_calc {
+                        let action = if more >= 0.5 {
+                            format!("Add ${:.0} on {} at {:.3}", more, selected_side_label, decimal_odds.unwrap_or(f64::NAN))
+                        } else if hedge >= 0.5 {
+                            format!("Hedge ${:.0} on {} at {:.3}", hedge, other_side_label, dh)
+                        } else {
+                            "Hold — no additional bet".to_string()
+                        };
+                        html!{
+                            <>
+                                <div class="section-divider"></div>
+                                <div class="muted">{"With Current Position"}</div>
+                                <div class="result success" style="font-size:20px;">{action}</div>
+                                <div class="metric-grid">
+                                    <div class="metric-item">
+                                        <div class="metric-value">{format!("${:.0} → ${:.0}", before[0], after[0])}</div>
+                                        <div class="metric-label">{format!("Bankroll if {} wins", selected_side_label)}</div>
+                                    </div>
+                                    <div class="metric-item">
+                                        <div class="metric-value">{format!("${:.0} → ${:.0}", before[1], after[1])}</div>
+                                        <div class="metric-label">{format!("Bankroll if {} wins", other_side_label)}</div>
+                                    </div>
+                                </div>
+                                <div class="hint" style="margin-top:12px;">{"Kelly on the total portfolio: open position plus any new bet (boosts ignored)."}</div>
+                            </>
+                        }
+                    } else { html!{} }}
                 </div>
 
                 <div class="card">
@@ -625,7 +1882,7 @@ fn app() -> Html {
                         } else { html!{} }}
                     </div>
                     
-                    { if multi_rows.len() > 0 {
+                    { if !multi_rows.is_empty() {
                         html!{
                             <div>
                                 { for multi_rows.iter().map(|(r, d, f)| {
@@ -634,6 +1891,7 @@ fn app() -> Html {
                                     let rec_pct = 100.0 * rec;
                                     html!{ 
                                         <div style="padding:8px; background:rgba(255,255,255,0.02); border-radius:6px; margin-bottom:6px;">
+                                            { place_button(BetRecord::new(r.name.clone(), "Multiple Outcomes".into(), String::new(), *d, r.yours, bankroll_val()*rec, rec).with_market(r.mkt)) }
                                             <strong>{&r.name}</strong>
                                             <div style="font-size:12px; color: var(--muted); margin-top:2px;">
                                                 {format!("Kelly: {:.1}% → Recommend: {:.1}% (odds {:.2})", kelly_pct, rec_pct, d)}
@@ -650,11 +1908,55 @@ fn app() -> Html {
                 </div>
             </div>
 
+            <div class="card">
+                <h2>
+                    <span>{"Live Odds"}</span>
+                    <span class={format!("status-indicator {}", match live.status { LiveStatus::Live => "success", LiveStatus::Connecting => "warning", LiveStatus::Closed(_) => "danger", LiveStatus::Off => "" })}>
+                        { match &live.status {
+                            LiveStatus::Off => "Off".to_string(),
+                            LiveStatus::Connecting => "Connecting…".to_string(),
+                            LiveStatus::Live => format!("Live — {} ids", live_ids.len()),
+                            LiveStatus::Closed(reason) => reason.clone(),
+                        }}
+                    </span>
+                </h2>
+                <div class="hint" style="margin-bottom:12px;">{"Stream prices over a WebSocket. Give Compare rows a Feed ID (and the single bet one below); updates for those ids replace the odds and changed recommendations are highlighted. Run `cargo run --bin mock_odds_server` for a local test feed."}</div>
+                {{
+                    let live_url_set = live_url.clone();
+                    let on_url = Callback::from(move |e: InputEvent| {
+                        let t: HtmlInputElement = e.target_unchecked_into();
+                        live_url_set.set(t.value());
+                    });
+                    let single_live_id_set = single_live_id.clone();
+                    let on_single_id = Callback::from(move |e: InputEvent| {
+                        let t: HtmlInputElement = e.target_unchecked_into();
+                        single_live_id_set.set(t.value());
+                    });
+                    html!{
+                        <>
+                            <div class="row">
+                                <div class="input-group">
+                                    <label>{"Server URL"}</label>
+                                    <input value={(*live_url).clone()} oninput={on_url} disabled={live_active} aria-label="Live odds server URL" />
+                                </div>
+                                <div class="input-group">
+                                    <label>{"Single Bet Feed ID"}</label>
+                                    <input placeholder={"optional"} value={(*single_live_id).clone()} oninput={on_single_id} aria-label="Single bet feed id" />
+                                </div>
+                            </div>
+                            <button class={if live_active { "danger" } else { "" }} onclick={on_live_toggle} style="width:100%;">
+                                { if live_active { "Disconnect" } else { "Connect" } }
+                            </button>
+                        </>
+                    }
+                }}
+            </div>
+
             <div class="card">
                 <h2>
                     <span>{"Compare Bets (Live)"}</span>
                 </h2>
-                <div class="hint" style="margin-bottom:12px;">{"Add selections across one or more markets (groups). Odds can be decimal, American, or fractional."}</div>
+                <div class="hint" style="margin-bottom:12px;">{"Add selections across one or more markets (groups). Odds can be decimal, American, or fractional. Enter the same selection name once per book to shop for the best price."}</div>
 
                 <div>
                     { for (*compares).iter().enumerate().map(|(i, r)| {
@@ -686,6 +1988,41 @@ fn app() -> Html {
                             v[i].your = t.value().parse::<f64>().unwrap_or(0.0).clamp(0.0, 100.0);
                             compares_set4.set(v);
                         });
+                        let compares_set6 = compares.clone();
+                        let on_boost = Callback::from(move |e: InputEvent| {
+                            let mut v = (*compares_set6).clone();
+                            let t: HtmlInputElement = e.target_unchecked_into();
+                            v[i].boost = t.value();
+                            compares_set6.set(v);
+                        });
+                        let compares_set7 = compares.clone();
+                        let on_boost_cap = Callback::from(move |e: InputEvent| {
+                            let mut v = (*compares_set7).clone();
+                            let t: HtmlInputElement = e.target_unchecked_into();
+                            v[i].boost_cap = t.value();
+                            compares_set7.set(v);
+                        });
+                        let compares_set9 = compares.clone();
+                        let on_book = Callback::from(move |e: InputEvent| {
+                            let mut v = (*compares_set9).clone();
+                            let t: HtmlInputElement = e.target_unchecked_into();
+                            v[i].book = t.value();
+                            compares_set9.set(v);
+                        });
+                        let compares_set10 = compares.clone();
+                        let on_id = Callback::from(move |e: InputEvent| {
+                            let mut v = (*compares_set10).clone();
+                            let t: HtmlInputElement = e.target_unchecked_into();
+                            v[i].id = t.value();
+                            compares_set10.set(v);
+                        });
+                        let compares_set8 = compares.clone();
+                        let on_parlay = Callback::from(move |e: Event| {
+                            let mut v = (*compares_set8).clone();
+                            let t: HtmlInputElement = e.target_unchecked_into();
+                            v[i].parlay = t.checked();
+                            compares_set8.set(v);
+                        });
                         let compares_set5 = compares.clone();
                         let on_remove = Callback::from(move |_| {
                             let mut v = (*compares_set5).clone();
@@ -702,6 +2039,10 @@ fn app() -> Html {
                                     <label>{"Group (market)"}</label>
                                     <input value={r.group.clone()} oninput={on_group} aria-label="Compare group" />
                                 </div>
+                                <div>
+                                    <label>{"Book"}</label>
+                                    <input placeholder={"e.g. Pinnacle"} value={r.book.clone()} oninput={on_book} aria-label="Compare book" />
+                                </div>
                                 <div>
                                     <label>{"Odds"}</label>
                                     <input placeholder={"e.g. 2.10, +110, 11/10"} value={r.odds.clone()} oninput={on_odds} aria-label="Compare odds" />
@@ -710,6 +2051,22 @@ fn app() -> Html {
                                     <label>{"Your %"}</label>
                                     <input type="number" min="0" max="100" step="0.1" value={format!("{:.1}", r.your)} oninput={on_your} aria-label="Your probability" />
                                 </div>
+                                <div>
+                                    <label>{"Boost"}</label>
+                                    <input placeholder={"e.g. 2.50 or 25%"} value={r.boost.clone()} oninput={on_boost} aria-label="Compare boost" />
+                                </div>
+                                <div>
+                                    <label>{"Boost max ($)"}</label>
+                                    <input placeholder={"no cap"} value={r.boost_cap.clone()} oninput={on_boost_cap} aria-label="Compare boost max stake" />
+                                </div>
+                                <div>
+                                    <label>{"Feed ID"}</label>
+                                    <input placeholder={"live odds key"} value={r.id.clone()} oninput={on_id} aria-label="Compare live feed id" />
+                                </div>
+                                <div>
+                                    <label>{"Parlay leg"}</label>
+                                    <input type="checkbox" style="width:auto;" checked={r.parlay} onchange={on_parlay} aria-label="Use in parlay" />
+                                </div>
                                 <button onclick={on_remove} class="danger" style="height:40px;">{"Remove"}</button>
                             </div>
                         }
@@ -717,20 +2074,207 @@ fn app() -> Html {
                     <button onclick={on_add_compare.clone()} style="margin-top:8px; width:100%;">{"Add Selection"}</button>
                 </div>
 
+                <details style="margin-top:12px;">
+                    <summary class="muted" style="cursor:pointer;">{"Import CSV / paste from spreadsheet"}</summary>
+                    {{
+                        let records = parse_delimited(&csv_text);
+                        let header: Vec<String> = records.first().map(|r| r.1.clone()).unwrap_or_default();
+                        let auto = csv_auto_map(&header);
+                        let mapping: Vec<Option<usize>> = csv_map.iter().zip(auto.iter()).map(|(o, a)| o.unwrap_or(*a)).collect();
+                        let csv_text_set = csv_text.clone();
+                        let csv_map_reset = csv_map.clone();
+                        let on_text = Callback::from(move |e: InputEvent| {
+                            let t: web_sys::HtmlTextAreaElement = e.target_unchecked_into();
+                            csv_text_set.set(t.value());
+                            csv_map_reset.set(vec![None; CSV_FIELDS.len()]);
+                        });
+                        let csv_text_set2 = csv_text.clone();
+                        let csv_map_reset2 = csv_map.clone();
+                        let on_loaded = Callback::from(move |text: String| {
+                            csv_text_set2.set(text);
+                            csv_map_reset2.set(vec![None; CSV_FIELDS.len()]);
+                        });
+                        let reader = csv_reader.clone();
+                        let on_file = Callback::from(move |e: Event| read_picked_file(e, reader.clone(), on_loaded.clone()));
+                        let csv_append_set = csv_append.clone();
+                        let on_append = Callback::from(move |e: Event| {
+                            let t: HtmlInputElement = e.target_unchecked_into();
+                            csv_append_set.set(t.checked());
+                        });
+                        let (compares_set, csv_report_set, append) = (compares.clone(), csv_report.clone(), *csv_append);
+                        let csv_odds_format_set = csv_odds_format.clone();
+                        let on_odds_format = Callback::from(move |e: Event| {
+                            let t: HtmlSelectElement = e.target_unchecked_into();
+                            csv_odds_format_set.set((t.selected_index() as usize).checked_sub(1).and_then(|i| OddsFormat::all().get(i)).map(|f| f.0));
+                        });
+                        let (import_records, import_map, import_format) = (records.clone(), mapping.clone(), *csv_odds_format);
+                        let on_import = Callback::from(move |_| {
+                            let (rows, errors) = csv_compare_rows(import_records.get(1..).unwrap_or_default(), &import_map, import_format);
+                            let n = rows.len();
+                            if n > 0 {
+                                let mut v = if append { (*compares_set).clone() } else { Vec::new() };
+                                v.extend(rows);
+                                compares_set.set(v);
+                            }
+                            csv_report_set.set(Some((n, errors)));
+                        });
+                        let ready = records.len() > 1 && [CSV_NAME, CSV_ODDS, CSV_PROB].iter().all(|&f| mapping[f].is_some());
+                        html!{
+                            <div style="margin-top:8px;">
+                                <div class="hint" style="margin-bottom:8px;">{"First line is the header. Comma, semicolon and tab separated text are detected; paste cells straight from a spreadsheet. Pick the odds format of the file (with \"Any\", 150 reads as decimal; +150 and 3/2 are recognised). Your % as 0–100 (a column of values all ≤ 1 is read as fractions)."}</div>
+                                <textarea rows="5" placeholder={"name,group,book,odds,prob\nChiefs -3,NFL Week 1,Book A,1.91,54.5"} value={(*csv_text).clone()} oninput={on_text} aria-label="CSV text" />
+                                <div class="input-group" style="margin-top:8px;">
+                                    <label>{"Or load a file"}</label>
+                                    <input type="file" accept=".csv,.tsv,.txt,text/csv,text/tab-separated-values" onchange={on_file} aria-label="Import CSV file" />
+                                </div>
+                                { if header.is_empty() { html!{} } else { html!{
+                                    <div class="row three">
+                                        { for CSV_FIELDS.iter().enumerate().map(|(f, (label, _))| {
+                                            let csv_map_set = csv_map.clone();
+                                            let on_map = Callback::from(move |e: Event| {
+                                                let mut v = (*csv_map_set).clone();
+                                                let t: HtmlSelectElement = e.target_unchecked_into();
+                                                let idx = t.selected_index();
+                                                v[f] = Some(if idx <= 0 { None } else { Some(idx as usize - 1) });
+                                                csv_map_set.set(v);
+                                            });
+                                            let required = [CSV_NAME, CSV_ODDS, CSV_PROB].contains(&f);
+                                            html!{
+                                                <div class="input-group">
+                                                    <label>{ if required { format!("{} *", label) } else { label.to_string() } }</label>
+                                                    <select onchange={on_map} aria-label={format!("Column for {}", label)}>
+                                                        <option selected={mapping[f].is_none()}>{"—"}</option>
+                                                        { for header.iter().enumerate().map(|(c, h)| html!{ <option selected={mapping[f] == Some(c)}>{ if h.trim().is_empty() { format!("Column {}", c + 1) } else { h.clone() } }</option> }) }
+                                                    </select>
+                                                </div>
+                                            }
+                                        }) }
+                                        <div class="input-group">
+                                            <label>{"Odds Format"}</label>
+                                            <select onchange={on_odds_format} aria-label="CSV odds format">
+                                                <option selected={csv_odds_format.is_none()}>{"Any"}</option>
+                                                { for OddsFormat::all().iter().map(|(f, name)| html!{ <option selected={*csv_odds_format == Some(*f)}>{ *name }</option> }) }
+                                            </select>
+                                        </div>
+                                    </div>
+                                }}}
+                                <div style="display:flex; align-items:center; gap:12px; margin-top:8px;">
+                                    <button onclick={on_import} disabled={!ready}>{format!("Import {} rows", records.len().saturating_sub(1))}</button>
+                                    <label style="display:flex; align-items:center; gap:6px; margin:0;">
+                                        <input type="checkbox" style="width:auto;" checked={*csv_append} onchange={on_append} aria-label="Append to existing rows" />
+                                        {"Append instead of replacing"}
+                                    </label>
+                                </div>
+                                { match &*csv_report {
+                                    Some((n, errors)) => html!{
+                                        <>
+                                            <div class={if errors.is_empty() { "hint success" } else { "hint warning" }}>
+                                                {format!("Imported {} rows, skipped {}", n, errors.len())}
+                                            </div>
+                                            { for errors.iter().map(|e| html!{ <div class="hint danger">{e}</div> }) }
+                                        </>
+                                    },
+                                    None => html!{},
+                                }}
+                            </div>
+                        }
+                    }}
+                </details>
+
+                <details style="margin-top:12px;">
+                    <summary class="muted" style="cursor:pointer;">{"Import odds feed JSON"}</summary>
+                    {{
+                        let feed_text_set = feed_text.clone();
+                        let on_text = Callback::from(move |e: InputEvent| {
+                            let t: web_sys::HtmlTextAreaElement = e.target_unchecked_into();
+                            feed_text_set.set(t.value());
+                        });
+                        let feed_text_set2 = feed_text.clone();
+                        let on_loaded = Callback::from(move |text: String| feed_text_set2.set(text));
+                        let reader = feed_reader.clone();
+                        let on_file = Callback::from(move |e: Event| read_picked_file(e, reader.clone(), on_loaded.clone()));
+                        let feed_replace_set = feed_replace.clone();
+                        let on_replace = Callback::from(move |e: Event| {
+                            let t: HtmlInputElement = e.target_unchecked_into();
+                            feed_replace_set.set(t.checked());
+                        });
+                        let (compares_set, feed_report_set, text, replace) = (compares.clone(), feed_report.clone(), (*feed_text).clone(), *feed_replace);
+                        let on_import = Callback::from(move |_| {
+                            match odds_api_rows(&text) {
+                                Ok(rows) => {
+                                    let n = rows.len();
+                                    compares_set.set(merge_compare_rows(if replace { &[] } else { &compares_set }, rows));
+                                    feed_report_set.set(Some(Ok(n)));
+                                }
+                                Err(e) => feed_report_set.set(Some(Err(e))),
+                            }
+                        });
+                        html!{
+                            <div style="margin-top:8px;">
+                                <div class="hint" style="margin-bottom:8px;">{"Paste or load an odds-API response (a list of events with bookmakers, markets and outcomes). Each event/market becomes a group with one row per outcome and book. Rows already present keep their Your %; new rows start at the no-vig consensus, so only your model's probabilities need entering."}</div>
+                                <textarea rows="5" placeholder={"[{\"home_team\": ..., \"away_team\": ..., \"bookmakers\": [...]}]"} value={(*feed_text).clone()} oninput={on_text} aria-label="Odds feed JSON" />
+                                <div class="input-group" style="margin-top:8px;">
+                                    <label>{"Or load a file"}</label>
+                                    <input type="file" accept=".json,application/json" onchange={on_file} aria-label="Import odds feed file" />
+                                </div>
+                                <div style="display:flex; align-items:center; gap:12px;">
+                                    <button onclick={on_import} disabled={feed_text.trim().is_empty()}>{"Import Odds"}</button>
+                                    <label style="display:flex; align-items:center; gap:6px; margin:0;">
+                                        <input type="checkbox" style="width:auto;" checked={*feed_replace} onchange={on_replace} aria-label="Replace existing rows" />
+                                        {"Replace existing rows"}
+                                    </label>
+                                </div>
+                                { match &*feed_report {
+                                    Some(Ok(n)) => html!{ <div class="hint success">{format!("Imported {} prices", n)}</div> },
+                                    Some(Err(e)) => html!{ <div class="hint danger">{e}</div> },
+                                    None => html!{},
+                                }}
+                            </div>
+                        }
+                    }}
+                </details>
+
                 <div style="margin-top:12px;">
                     { for compare_view.iter().map(|(g, sum_f, scale, items)| {
                         html!{
                             <div style="margin-bottom:12px;">
                                 <div class="muted">{format!("Group: {} — total Kelly {:.1}% (scaled: {}x)", g, 100.0*sum_f, format!("{:.2}", scale))}</div>
-                                { for items.iter().map(|(idx, d, f, _imp, ev)| {
-                                    let r = &cmp_rows[*idx];
-                                    let rec = f * *scale;
+                                { for items.iter().map(|c| {
+                                    let r = &cmp_rows[c.idx];
+                                    let rec = c.f * *scale;
+                                    let odds_str = if c.d_boost > c.d {
+                                        let cap = if c.cap.is_finite() { format!(" to ${:.0}", c.cap * bank_for_cmp) } else { String::new() };
+                                        format!("{:.3} (boost {:.3}{})", c.d, c.d_boost, cap)
+                                    } else { format!("{:.3}", c.d) };
+                                    let book_label = |i: usize| if cmp_rows[i].book.trim().is_empty() { format!("row {}", i + 1) } else { cmp_rows[i].book.clone() };
+                                    let moved = std::iter::once(c.idx).chain(c.others.iter().map(|o| o.0))
+                                        .filter(|i| live_changed.iter().any(|id| id == cmp_rows[*i].id.trim()))
+                                        .find_map(|i| live.moved.get(cmp_rows[i].id.trim()).map(|m| (i, *m)));
                                     html!{
-                                        <div style="padding:8px; background:rgba(255,255,255,0.02); border-radius:6px; margin-top:6px;">
-                                            <strong>{&r.name}</strong>{" — "}{format!("{}", &r.group)}
+                                        <div style={format!("padding:8px; background:rgba(255,255,255,0.02); border-radius:6px; margin-top:6px;{}", if moved.is_some() { " box-shadow: inset 3px 0 0 var(--warning);" } else { "" })}>
+                                            { place_button(BetRecord::new(r.name.clone(), r.group.clone(), r.book.clone(), 1.0 + boosted_b(rec, c.d, c.d_boost, c.cap), r.your, bank_for_cmp*rec, rec).with_market(100.0 / c.d)) }
+                                            <strong>{&r.name}</strong>{" — "}{&r.group}
+                                            { if r.book.trim().is_empty() { html!{} } else { html!{ <span class="muted">{format!(" @ {}", r.book)}</span> } }}
                                             <div style="font-size:12px; color: var(--muted); margin-top:2px;">
-                                                {format!("Odds {:.3} | Kelly {:.1}% → Recommend {:.1}% | EV/1 {:+.3} | Stake ${:.0}", d, 100.0*(*f), 100.0*rec, ev, bank_for_cmp*rec)}
+                                                {format!("Odds {} | Kelly {:.1}% → Recommend {:.1}% | EV/1 {:+.3} | Stake ${:.0}", odds_str, 100.0*c.f, 100.0*rec, c.ev, bank_for_cmp*rec)}
                                             </div>
+                                            { match moved {
+                                                Some((i, (old, new))) => html!{
+                                                    <div class="warning" style="font-size:12px;">{format!("Live: {} {:.3} → {:.3}", book_label(i), old, new)}</div>
+                                                },
+                                                None => html!{},
+                                            }}
+                                            { if c.others.is_empty() { html!{} } else {
+                                                let others = c.others.iter().map(|(i, d)| match parse_boost(&cmp_rows[*i].boost, *d) {
+                                                    Some(db) if db != *d => format!("{} {:.3} (boosted {:.3})", book_label(*i), d, db),
+                                                    _ => format!("{} {:.3}", book_label(*i), d),
+                                                }).collect::<Vec<_>>().join(", ");
+                                                html!{
+                                                    <div style="font-size:12px; color: var(--muted);">
+                                                        {format!("Best of {} books | avg {:.3} ({:+.1}% vs avg) | Others: {}", c.others.len() + 1, c.avg, 100.0*(c.d/c.avg - 1.0), others)}
+                                                    </div>
+                                                }
+                                            }}
                                         </div>
                                     }
                                 }) }
@@ -738,92 +2282,1483 @@ fn app() -> Html {
                         }
                     }) }
                 </div>
-            </div>
-
-            <div class="card">
-                <h2>
-                    <span>{"Three-Way Market (Exact Kelly)"}</span>
-                    <span class={if (three_sum - 1.0).abs() < 1e-6 { "status-indicator success" } else { "status-indicator" }}>
-                        {format!("Total stake: {:.1}%", 100.0*three_sum)}
-                    </span>
-                </h2>
-                <div class="hint" style="margin-bottom:12px;">{"Enter market vs your probabilities for 3 mutually exclusive outcomes (e.g., Team A / Draw / Team B). This computes the exact Kelly allocation across outcomes."}</div>
 
-                { for (*three).iter().enumerate().map(|(i, r)| {
-                    let three_set = three.clone();
-                    let on_name = Callback::from(move |e: InputEvent| {
-                        let mut v = (*three_set).clone();
+                <div class="section-divider"></div>
+                <div class="muted" style="margin-bottom:8px;">{"Sure Bets & Middles"}</div>
+                <div class="hint" style="margin-bottom:12px;">{"Uses the best price per selection name in each group. Sure bets assume the group's selections cover every outcome; middles are read from names like \"Over 47.5\" / \"Under 48.5\" or \"Team A -3\" / \"Team B +4.5\"."}</div>
+                {{
+                    let arb_stake_set = arb_stake.clone();
+                    let on_arb_stake = Callback::from(move |e: InputEvent| {
                         let t: HtmlInputElement = e.target_unchecked_into();
-                        v[i].name = t.value();
-                        three_set.set(v);
+                        arb_stake_set.set(t.value());
                     });
-                    let three_set2 = three.clone();
-                    let on_mkt = Callback::from(move |e: InputEvent| {
-                        let mut v = (*three_set2).clone();
-                        let t: HtmlInputElement = e.target_unchecked_into();
-                        v[i].mkt = t.value().parse::<f64>().unwrap_or(0.0).clamp(0.0, 100.0);
-                        three_set2.set(v);
+                    html!{
+                        <div class="input-group">
+                            <label>{"Total Stake ($)"}</label>
+                            <input value={(*arb_stake).clone()} oninput={on_arb_stake} aria-label="Sure bet total stake" />
+                        </div>
+                    }
+                }}
+                { if sure_bets.is_empty() && middles.is_empty() {
+                    html!{ <div class="hint">{"No sure bets or middles at current prices."}</div> }
+                } else { html!{} }}
+                { for sure_bets.iter().map(|sb| {
+                    html!{
+                        <div style="padding:8px; background:rgba(255,255,255,0.02); border-radius:6px; margin-top:6px;">
+                            <strong class="success">{format!("Sure bet — {}", sb.group)}</strong>
+                            <div style="font-size:12px; color: var(--muted); margin-top:2px;">
+                                {format!("Book {:.2}% → guaranteed profit {:.2}% (${:.2})", 100.0*sb.book, 100.0*(1.0/sb.book - 1.0), arb_total*(1.0/sb.book - 1.0))}
+                            </div>
+                            { for sb.legs.iter().map(|(idx, d, share)| html!{
+                                <div style="font-size:12px; color: var(--muted);">
+                                    {format!("{}{} @ {:.3}: stake ${:.2} ({:.1}%)", cmp_rows[*idx].name, if cmp_rows[*idx].book.trim().is_empty() { String::new() } else { format!(" ({})", cmp_rows[*idx].book) }, d, arb_total*share, 100.0*share)}
+                                </div>
+                            }) }
+                        </div>
+                    }
+                }) }
+                { for middles.iter().map(|m| {
+                    let (sa, sb) = (arb_total * m.shares.0, arb_total * m.shares.1);
+                    html!{
+                        <div style="padding:8px; background:rgba(255,255,255,0.02); border-radius:6px; margin-top:6px;">
+                            <strong class="warning">{format!("Middle — {}", m.group)}</strong>
+                            <div style="font-size:12px; color: var(--muted); margin-top:2px;">
+                                {format!("{} (${:.2}) + {} (${:.2}): middle between {} and {}", cmp_rows[m.a].name, sa, cmp_rows[m.b].name, sb, m.lo, m.hi)}
+                            </div>
+                            <div style="font-size:12px; color: var(--muted);">
+                                {format!("One side wins: {:+.2}", arb_total*(m.miss - 1.0))}
+                            </div>
+                            { for m.hits.iter().map(|(x, ret)| {
+                                let how = if *x == m.lo { format!("{} pushes", cmp_rows[m.a].name) }
+                                    else if *x == m.hi { format!("{} pushes", cmp_rows[m.b].name) }
+                                    else { "both win".to_string() };
+                                html!{
+                                    <div style="font-size:12px; color: var(--muted);">
+                                        {format!("Result {}: {:+.2} ({})", x, arb_total*(ret - 1.0), how)}
+                                    </div>
+                                }
+                            }) }
+                        </div>
+                    }
+                }) }
+
+                <div class="section-divider"></div>
+                <div class="muted" style="margin-bottom:8px;">{"Correlations"}</div>
+                <div class="hint" style="margin-bottom:12px;">{"Declare pairwise correlations between selections of different groups (e.g. side and total of the same game). The joint Kelly allocation is then solved over correlated draws from a Gaussian copula; the selections of one group stay mutually exclusive."}</div>
+                { for (*correlations).iter().enumerate().map(|(i, c)| {
+                    let choices: Vec<(String, String)> = corr_legs.iter().map(|l| (l.1.name.clone(), l.1.group.clone())).collect();
+                    let choices2 = choices.clone();
+                    let correlations_set = correlations.clone();
+                    let on_a = Callback::from(move |e: Event| {
+                        let mut v = (*correlations_set).clone();
+                        let t: HtmlSelectElement = e.target_unchecked_into();
+                        if let Some((name, group)) = (t.selected_index() as usize).checked_sub(1).and_then(|k| choices2.get(k)) {
+                            v[i].a = name.clone();
+                            v[i].a_group = group.clone();
+                            correlations_set.set(v);
+                        }
                     });
-                    let three_set3 = three.clone();
-                    let on_yours = Callback::from(move |e: InputEvent| {
-                        let mut v = (*three_set3).clone();
+                    let choices3 = choices.clone();
+                    let correlations_set2 = correlations.clone();
+                    let on_b = Callback::from(move |e: Event| {
+                        let mut v = (*correlations_set2).clone();
+                        let t: HtmlSelectElement = e.target_unchecked_into();
+                        if let Some((name, group)) = (t.selected_index() as usize).checked_sub(1).and_then(|k| choices3.get(k)) {
+                            v[i].b = name.clone();
+                            v[i].b_group = group.clone();
+                            correlations_set2.set(v);
+                        }
+                    });
+                    let pick = |name: &str, group: &str, onchange: Callback<Event>, label: &'static str| {
+                        let matches: Vec<usize> = choices.iter().enumerate()
+                            .filter(|(_, (n, g))| n.trim() == name.trim() && (group.trim().is_empty() || g.trim() == group.trim()))
+                            .map(|x| x.0).collect();
+                        let sel = if matches.len() == 1 { Some(matches[0]) } else { None };
+                        html!{
+                            <select {onchange} class={if sel.is_some() { "" } else { "error" }} aria-label={label}>
+                                <option selected={sel.is_none()} disabled=true>
+                                    { if name.trim().is_empty() { "Select…".to_string() } else { format!("{} (not found)", name) } }
+                                </option>
+                                { for choices.iter().enumerate().map(|(k, (n, g))| html!{
+                                    <option selected={sel == Some(k)}>{format!("{} — {}", n, g)}</option>
+                                }) }
+                            </select>
+                        }
+                    };
+                    let correlations_set3 = correlations.clone();
+                    let on_rho = Callback::from(move |e: InputEvent| {
+                        let mut v = (*correlations_set3).clone();
                         let t: HtmlInputElement = e.target_unchecked_into();
-                        v[i].yours = t.value().parse::<f64>().unwrap_or(0.0).clamp(0.0, 100.0);
-                        three_set3.set(v);
+                        v[i].rho = t.value().parse::<f64>().unwrap_or(0.0).clamp(-0.99, 0.99);
+                        correlations_set3.set(v);
+                    });
+                    let correlations_set4 = correlations.clone();
+                    let on_remove = Callback::from(move |_| {
+                        let mut v = (*correlations_set4).clone();
+                        if i < v.len() { v.remove(i); }
+                        correlations_set4.set(v);
                     });
                     html!{
-                        <div class="row three" style="gap:8px; margin-bottom:12px; align-items: end;">
+                        <div class="row four" style="gap:8px; margin-bottom:12px; align-items: end;">
                             <div>
-                                <label>{"Outcome"}</label>
-                                <input value={r.name.clone()} oninput={on_name} aria-label="Three-way outcome name" />
+                                <label>{"Selection A"}</label>
+                                { pick(&c.a, &c.a_group, on_a, "Correlated selection A") }
                             </div>
                             <div>
-                                <label>{"Market %"}</label>
-                                <input type="number" min="0" max="100" step="0.1" value={format!("{:.1}", r.mkt)} oninput={on_mkt} aria-label="Three-way market probability" />
+                                <label>{"Selection B"}</label>
+                                { pick(&c.b, &c.b_group, on_b, "Correlated selection B") }
                             </div>
                             <div>
-                                <label>{"Your %"}</label>
-                                <input type="number" min="0" max="100" step="0.1" value={format!("{:.1}", r.yours)} oninput={on_yours} aria-label="Three-way your probability" />
+                                <label>{"Correlation ρ"}</label>
+                                <input type="number" min="-0.99" max="0.99" step="0.05" value={format!("{:.2}", c.rho)} oninput={on_rho} aria-label="Correlation" />
                             </div>
+                            <button onclick={on_remove} class="danger" style="height:40px;">{"Remove"}</button>
                         </div>
                     }
-                })}
-                <div class="section-divider"></div>
-                <div>
-                    { for three_rows.iter().enumerate().map(|(i, r)| {
-                        let frac = three_alloc.get(i).cloned().unwrap_or(0.0);
-                        html!{
-                            <div style="padding:8px; background:rgba(255,255,255,0.02); border-radius:6px; margin-top:6px;">
-                                <strong>{&r.name}</strong>
-                                <div style="font-size:12px; color: var(--muted); margin-top:2px;">
-                                    {format!("Recommend: {:.1}% of bankroll → ${:.0}", 100.0*frac, bankroll_val()*frac)}
-                                </div>
+                }) }
+                {{
+                    let correlations_set = correlations.clone();
+                    // default pair: the first two selections from different groups
+                    let first = corr_legs.first().map(|l| l.1.clone());
+                    let second = first.as_ref().and_then(|a| corr_legs.iter().find(|l| l.1.group != a.group)).map(|l| l.1.clone());
+                    let on_add = Callback::from(move |_| {
+                        let mut v = (*correlations_set).clone();
+                        let (a, b) = (first.clone().unwrap_or(CorrLeg { group: String::new(), name: String::new(), d: 0.0, p: 0.0 }), second.clone());
+                        let (b_name, b_group) = b.map(|b| (b.name, b.group)).unwrap_or_default();
+                        v.push(CorrRow { a: a.name, a_group: a.group, b: b_name, b_group, rho: 0.3 });
+                        correlations_set.set(v);
+                    });
+                    html!{ <button onclick={on_add} class="secondary" style="width:100%;">{"Add Correlation"}</button> }
+                }}
+                { if let Some(calc) = corr_calc.as_ref() {
+                    html!{
+                        <div style="margin-top:12px;">
+                            { for corr_legs.iter().enumerate().map(|(j, (idx, leg))| {
+                                let (r, d) = (&cmp_rows[*idx], leg.d);
+                                let fc = calc.correlated.get(j).cloned().unwrap_or(0.0);
+                                let fi = calc.independent.get(j).cloned().unwrap_or(0.0);
+                                html!{
+                                    <div style="padding:8px; background:rgba(255,255,255,0.02); border-radius:6px; margin-top:6px;">
+                                        <strong>{&r.name}</strong>{" — "}{&r.group}
+                                        <div style="font-size:12px; color: var(--muted); margin-top:2px;">
+                                            {format!("Odds {:.3} | Correlated {:.1}% → ${:.0} | Independent {:.1}%", d, 100.0*fc, bank_for_cmp*fc, 100.0*fi)}
+                                        </div>
+                                    </div>
+                                }
+                            }) }
+                            <div class="hint" style="margin-top:12px;">
+                                {format!("Log growth: correlated {:+.1} bp vs independent {:+.1} bp ({} copula draws; boosts ignored)", calc.g_corr * 10_000.0, calc.g_indep * 10_000.0, COPULA_DRAWS)}
                             </div>
-                        }
-                    }) }
-                </div>
-                <div class="hint" style="margin-top:12px;">{"Optimization: maximize expected log growth under sum of stakes ≤ 100%."}</div>
+                            { if calc.shrink < 1.0 {
+                                html!{ <div class="hint warning">{format!("Correlations are inconsistent; scaled by {:.2} to form a valid matrix.", calc.shrink)}</div> }
+                            } else { html!{} }}
+                            { for calc.notes.iter().map(|n| html!{ <div class="hint warning">{n}</div> }) }
+                        </div>
+                    }
+                } else if !correlations.is_empty() && corr_legs.len() > COPULA_MAX_LEGS {
+                    html!{ <div class="hint warning" style="margin-top:12px;">{format!("The correlated allocation covers up to {} priced selections; {} are entered.", COPULA_MAX_LEGS, corr_legs.len())}</div> }
+                } else if !correlations.is_empty() {
+                    html!{ <div class="hint" style="margin-top:12px;">{"Price at least two selections to solve the correlated allocation."}</div> }
+                } else { html!{} }}
             </div>
 
-            <footer>
-                {"EdgeRunner v0.1 - Professional Kelly Criterion calculator for optimal bet sizing"}
-            </footer>
-        </div>
-    }
-}
-
-fn parse_any(s: &str) -> Option<f64> { // decimal odds
-    let s = s.trim();
-    // try decimal
-    if let Ok(v) = s.parse::<f64>() { if v > 1.0 { return Some(v); } }
-    // american
-    if let Some(d) = parse_american(s) { return Some(d); }
-    // fractional
-    if let Some(d) = parse_fractional(s) { return Some(d); }
-    None
-}
-
-fn parse_american(s: &str) -> Option<f64> { // returns decimal odds
+            <div class="card">
+                <h2>
+                    <span>{"Parlay Builder"}</span>
+                    <span class="status-indicator">{format!("{} legs", parlay_legs.len())}</span>
+                </h2>
+                <div class="hint" style="margin-bottom:12px;">
+                    {format!("Tick \"Parlay leg\" on 2–{} Compare selections from different groups. Odds multiply; the correlation adjustment scales the chance that every leg wins (positive for legs that tend to win together) while keeping each leg's own probability, and the singles are sized over the same joint outcomes.", PARLAY_MAX_LEGS)}
+                </div>
+                {{
+                    let parlay_adj_set = parlay_adj.clone();
+                    let on_adj = Callback::from(move |e: InputEvent| {
+                        let t: HtmlInputElement = e.target_unchecked_into();
+                        parlay_adj_set.set(t.value().parse::<f64>().unwrap_or(0.0).clamp(-100.0, 1000.0));
+                    });
+                    html!{
+                        <div class="input-group">
+                            <label class="tooltip" data-tooltip="Relative change to the joint probability vs independent legs">
+                                {"Correlation Adjustment (%)"}
+                            </label>
+                            <input type="number" step="1" value={format!("{:.0}", *parlay_adj)} oninput={on_adj} aria-label="Parlay correlation adjustment" />
+                        </div>
+                    }
+                }}
+                { if let Some((a, b)) = parlay_conflict {
+                    html!{ <div class="hint warning">{format!("{} and {} are outcomes of the same group ({}); pick legs from different groups.", cmp_rows[a].name, cmp_rows[b].name, cmp_rows[a].group)}</div> }
+                } else if parlay_legs.len() > PARLAY_MAX_LEGS {
+                    html!{ <div class="hint warning">{format!("Select at most {} legs.", PARLAY_MAX_LEGS)}</div> }
+                } else if parlay_ok {
+                    html!{
+                        <>
+                            { for parlay_legs.iter().enumerate().map(|(j, (idx, d, p))| {
+                                let r = &cmp_rows[*idx];
+                                let single = singles_f.get(j).cloned().unwrap_or(0.0);
+                                html!{
+                                    <div style="padding:8px; background:rgba(255,255,255,0.02); border-radius:6px; margin-top:6px;">
+                                        <strong>{&r.name}</strong>{" — "}{&r.group}
+                                        <div style="font-size:12px; color: var(--muted); margin-top:2px;">
+                                            {format!("Odds {:.3} | Your {:.1}% | As single: {:.1}% → ${:.0}", d, 100.0*p, 100.0*single, bank_for_cmp*single)}
+                                        </div>
+                                    </div>
+                                }
+                            }) }
+                            <div class="metric-grid">
+                                <div class="metric-item">
+                                    <div class="metric-value">{format!("{} | {} | {}", format_decimal(parlay_d), format_american(parlay_d), format_fractional(parlay_d))}</div>
+                                    <div class="metric-label">{"Parlay Odds"}</div>
+                                </div>
+                                <div class="metric-item">
+                                    <div class="metric-value">{format!("{:.2}% (indep. {:.2}%)", 100.0*parlay_p, 100.0*parlay_p_indep)}</div>
+                                    <div class="metric-label">{"Joint Probability"}</div>
+                                </div>
+                                <div class="metric-item">
+                                    <div class={format!("metric-value {}", if parlay_ev > 0.0 { "success" } else { "danger" })}>{format!("{:+.3}", parlay_ev)}</div>
+                                    <div class="metric-label">{"EV per $1"}</div>
+                                </div>
+                                <div class="metric-item">
+                                    <div class="metric-value">{format!("{:.2}% → ${:.0}", 100.0*parlay_f, bank_for_cmp*parlay_f)}</div>
+                                    <div class="metric-label">{"Parlay Kelly"}</div>
+                                </div>
+                                <div class="metric-item">
+                                    <div class="metric-value">{format!("{:.2}% → ${:.0}", 100.0*singles_sum, bank_for_cmp*singles_sum)}</div>
+                                    <div class="metric-label">{"Singles Kelly (Total)"}</div>
+                                </div>
+                                <div class="metric-item">
+                                    <div class={format!("metric-value {}", if parlay_g > singles_g { "success" } else { "" })}>
+                                        {format!("{:+.1} | {:+.1} bp", parlay_g * 10_000.0, singles_g * 10_000.0)}
+                                    </div>
+                                    <div class="metric-label">{"Log Growth: Parlay | Singles"}</div>
+                                </div>
+                            </div>
+                            <div style="margin-top:8px; overflow:hidden;">
+                                { place_button(BetRecord::new(
+                                    parlay_legs.iter().map(|l| cmp_rows[l.0].name.clone()).collect::<Vec<_>>().join(" + "),
+                                    "Parlay".into(), String::new(), parlay_d, 100.0*parlay_p, bank_for_cmp*parlay_f, parlay_f)) }
+                            </div>
+                            <div class="hint" style="margin-top:12px;">
+                                { if parlay_g > singles_g {
+                                    "The parlay grows the bankroll faster than staking the legs as singles."
+                                } else {
+                                    "Staking the legs as singles grows the bankroll at least as fast."
+                                }}
+                            </div>
+                        </>
+                    }
+                } else {
+                    html!{ <div class="hint">{"Select at least two legs with valid odds."}</div> }
+                }}
+            </div>
+
+            <div class="card">
+                <h2>
+                    <span>{"Elo Model"}</span>
+                    <span class="status-indicator">{format!("Rating diff {:+.0}", elo.home + elo.home_adv - elo.away)}</span>
+                </h2>
+                <div class="hint" style="margin-bottom:12px;">{"Win/draw/loss probabilities from two Elo ratings. Without a draw model the logistic Elo curve splits 100% between the teams; the Davidson model carves out a draw share that peaks at equal ratings."}</div>
+                {{
+                    let elo_probs = elo_probs(&elo);
+                    let elo_set = elo.clone();
+                    let on_home = Callback::from(move |e: InputEvent| {
+                        let mut v = (*elo_set).clone();
+                        let t: HtmlInputElement = e.target_unchecked_into();
+                        v.home = t.value().parse::<f64>().unwrap_or(0.0);
+                        elo_set.set(v);
+                    });
+                    let elo_set2 = elo.clone();
+                    let on_away = Callback::from(move |e: InputEvent| {
+                        let mut v = (*elo_set2).clone();
+                        let t: HtmlInputElement = e.target_unchecked_into();
+                        v.away = t.value().parse::<f64>().unwrap_or(0.0);
+                        elo_set2.set(v);
+                    });
+                    let elo_set3 = elo.clone();
+                    let on_adv = Callback::from(move |e: InputEvent| {
+                        let mut v = (*elo_set3).clone();
+                        let t: HtmlInputElement = e.target_unchecked_into();
+                        v.home_adv = t.value().parse::<f64>().unwrap_or(0.0);
+                        elo_set3.set(v);
+                    });
+                    let elo_set4 = elo.clone();
+                    let on_draws = Callback::from(move |e: Event| {
+                        let mut v = (*elo_set4).clone();
+                        let t: HtmlInputElement = e.target_unchecked_into();
+                        v.draws = t.checked();
+                        elo_set4.set(v);
+                    });
+                    let elo_set5 = elo.clone();
+                    let on_draw_pct = Callback::from(move |e: InputEvent| {
+                        let mut v = (*elo_set5).clone();
+                        let t: HtmlInputElement = e.target_unchecked_into();
+                        v.draw_pct = t.value().parse::<f64>().unwrap_or(0.0).clamp(0.0, 95.0);
+                        elo_set5.set(v);
+                    });
+                    let to_single = |k: usize| {
+                        let (your_prob, push_prob, pct) = (your_prob.clone(), push_prob.clone(), 100.0 * elo_probs[k]);
+                        Callback::from(move |_| {
+                            your_prob.set((pct * 10.0).round() / 10.0);
+                            push_prob.set(0.0);
+                        })
+                    };
+                    let three_set = three.clone();
+                    let to_three = Callback::from(move |_| {
+                        let mut v = (*three_set).clone();
+                        for (r, p) in v.iter_mut().zip(elo_probs) { r.yours = (1000.0 * p).round() / 10.0; }
+                        three_set.set(v);
+                    });
+                    html!{
+                        <>
+                            <div class="row three">
+                                <div>
+                                    <label>{"Home Elo"}</label>
+                                    <input type="number" step="1" value={format!("{}", elo.home)} oninput={on_home} aria-label="Home Elo rating" />
+                                </div>
+                                <div>
+                                    <label>{"Away Elo"}</label>
+                                    <input type="number" step="1" value={format!("{}", elo.away)} oninput={on_away} aria-label="Away Elo rating" />
+                                </div>
+                                <div>
+                                    <label>{"Home Advantage (pts)"}</label>
+                                    <input type="number" step="1" value={format!("{}", elo.home_adv)} oninput={on_adv} aria-label="Home advantage in Elo points" />
+                                </div>
+                            </div>
+                            <div class="row" style="align-items:end;">
+                                <div class="input-group">
+                                    <label class="tooltip" data-tooltip="Davidson model: draw weight ν·√(home·away strength)">
+                                        <input type="checkbox" style="width:auto; margin-right:6px;" checked={elo.draws} onchange={on_draws} aria-label="Model draws" />
+                                        {"Model draws (Davidson)"}
+                                    </label>
+                                </div>
+                                <div class="input-group">
+                                    <label>{"Draw % at Equal Ratings"}</label>
+                                    <input type="number" min="0" max="95" step="0.5" value={format!("{}", elo.draw_pct)} oninput={on_draw_pct} disabled={!elo.draws} aria-label="Draw probability at equal ratings" />
+                                </div>
+                            </div>
+                            <div class="metric-grid">
+                                { for ["Home", "Draw", "Away"].iter().zip(elo_probs).map(|(label, p)| html!{
+                                    <div class="metric-item">
+                                        <div class="metric-value">{format!("{:.1}%", 100.0 * p)}</div>
+                                        <div class="metric-label">{ if p > 0.0 { format!("{} — fair {}", label, format_decimal(1.0 / p)) } else { label.to_string() } }</div>
+                                    </div>
+                                }) }
+                            </div>
+                            <div class="row four" style="margin-top:12px;">
+                                <button class="secondary" onclick={to_single(0)}>{"Home → Single Bet"}</button>
+                                <button class="secondary" onclick={to_single(1)} disabled={!elo.draws}>{"Draw → Single Bet"}</button>
+                                <button class="secondary" onclick={to_single(2)}>{"Away → Single Bet"}</button>
+                                <button class="secondary" onclick={to_three}>{"Use in Three-Way"}</button>
+                            </div>
+                            <div class="hint" style="margin-top:8px;">{"Single Bet takes the outcome as Your % for the selected side (no push); Three-Way sets Your % of Home / Draw / Away in row order."}</div>
+                        </>
+                    }
+                }}
+            </div>
+
+            <div class="card">
+                <h2>
+                    <span>{"Poisson Goal Model"}</span>
+                    <span class="status-indicator">{ if poisson.dixon_coles { "Dixon–Coles" } else { "Independent Poisson" } }</span>
+                </h2>
+                <div class="hint" style="margin-bottom:12px;">{"Score probabilities from each side's expected goals. The Dixon–Coles correction reweights 0-0, 1-0, 0-1 and 1-1 (negative ρ adds low-scoring draws). Send the results to the Single Bet, Three-Way or Asian Handicap panels."}</div>
+                {{
+                    let scores = score_matrix(&poisson);
+                    let line = totals_line(poisson.line);
+                    let gm = goal_markets(&scores, line);
+                    let picks: Vec<(String, f64, f64)> = vec![
+                        ("Home".into(), gm.home, 0.0), ("Draw".into(), gm.draw, 0.0), ("Away".into(), gm.away, 0.0),
+                        (format!("Over {}", line), gm.over, gm.push), (format!("Under {}", line), gm.under, gm.push),
+                        ("BTTS Yes".into(), gm.btts, 0.0), ("BTTS No".into(), 1.0 - gm.btts, 0.0),
+                    ];
+                    let field = |f: fn(&mut PoissonInputs, f64), lo: f64, hi: f64| {
+                        let poisson_set = poisson.clone();
+                        Callback::from(move |e: InputEvent| {
+                            let mut v = (*poisson_set).clone();
+                            let t: HtmlInputElement = e.target_unchecked_into();
+                            f(&mut v, t.value().parse::<f64>().unwrap_or(0.0).clamp(lo, hi));
+                            poisson_set.set(v);
+                        })
+                    };
+                    let on_home = field(|v, x| v.home_xg = x, 0.0, 10.0);
+                    let on_away = field(|v, x| v.away_xg = x, 0.0, 10.0);
+                    let on_rho = field(|v, x| v.rho = x, -0.5, 0.5);
+                    let on_line = field(|v, x| v.line = totals_line(x), 0.0, 20.0);
+                    let poisson_set = poisson.clone();
+                    let on_dc = Callback::from(move |e: Event| {
+                        let mut v = (*poisson_set).clone();
+                        let t: HtmlInputElement = e.target_unchecked_into();
+                        v.dixon_coles = t.checked();
+                        poisson_set.set(v);
+                    });
+                    let poisson_pick_set = poisson_pick.clone();
+                    let on_pick = Callback::from(move |e: Event| {
+                        let t: HtmlSelectElement = e.target_unchecked_into();
+                        poisson_pick_set.set(t.selected_index().max(0) as usize);
+                    });
+                    let to_single = {
+                        let (your_prob, push_prob) = (your_prob.clone(), push_prob.clone());
+                        let (_, p, push) = picks[(*poisson_pick).min(picks.len() - 1)].clone();
+                        Callback::from(move |_| {
+                            your_prob.set((1000.0 * p).round() / 10.0);
+                            push_prob.set((1000.0 * push).round() / 10.0);
+                        })
+                    };
+                    let three_set = three.clone();
+                    let to_three = Callback::from(move |_| {
+                        let mut v = (*three_set).clone();
+                        for (r, p) in v.iter_mut().zip([gm.home, gm.draw, gm.away]) { r.yours = (1000.0 * p).round() / 10.0; }
+                        three_set.set(v);
+                    });
+                    let asian_set = asian.clone();
+                    let dist = goal_difference_dist(&scores);
+                    let to_asian = Callback::from(move |_| {
+                        let mut v = (*asian_set).clone();
+                        v.dist = dist.iter().map(|p| (1000.0 * p).round() / 10.0).collect();
+                        asian_set.set(v);
+                    });
+                    let shown = 6; // correct-score grid covers 0–5 goals per side
+                    let other = 1.0 - scores.iter().take(shown).map(|row| row.iter().take(shown).sum::<f64>()).sum::<f64>();
+                    html!{
+                        <>
+                            <div class="row">
+                                <div class="input-group">
+                                    <label>{"Home xG"}</label>
+                                    <input type="number" min="0" max="10" step="0.05" value={format!("{}", poisson.home_xg)} oninput={on_home} aria-label="Home expected goals" />
+                                </div>
+                                <div class="input-group">
+                                    <label>{"Away xG"}</label>
+                                    <input type="number" min="0" max="10" step="0.05" value={format!("{}", poisson.away_xg)} oninput={on_away} aria-label="Away expected goals" />
+                                </div>
+                            </div>
+                            <div class="row" style="align-items:end;">
+                                <div class="input-group">
+                                    <label class="tooltip" data-tooltip="τ(0,0) = 1 − λμρ, τ(0,1) = 1 + λρ, τ(1,0) = 1 + μρ, τ(1,1) = 1 − ρ">
+                                        <input type="checkbox" style="width:auto; margin-right:6px;" checked={poisson.dixon_coles} onchange={on_dc} aria-label="Dixon-Coles correction" />
+                                        {"Dixon–Coles correction"}
+                                    </label>
+                                </div>
+                                <div class="input-group">
+                                    <label>{"ρ (rho)"}</label>
+                                    <input type="number" min="-0.5" max="0.5" step="0.01" value={format!("{}", poisson.rho)} oninput={on_rho} disabled={!poisson.dixon_coles} aria-label="Dixon-Coles rho" />
+                                </div>
+                                <div class="input-group">
+                                    <label class="tooltip" data-tooltip="Whole or half lines; for a quarter line (2.25) bet half the stake on 2 and half on 2.5">{"Total Goals Line"}</label>
+                                    <input type="number" min="0" max="20" step="0.5" value={format!("{}", line)} oninput={on_line} aria-label="Total goals line" />
+                                </div>
+                            </div>
+                            <div class="metric-grid">
+                                { for picks.iter().map(|(label, p, push)| html!{
+                                    <div class="metric-item">
+                                        <div class="metric-value">{format!("{:.1}%", 100.0 * p)}</div>
+                                        <div class="metric-label">
+                                            { if *p > 0.0 { format!("{} — fair {}", label, format_decimal((1.0 - push) / p)) } else { label.clone() } }
+                                        </div>
+                                    </div>
+                                }) }
+                            </div>
+                            { if gm.push > 0.0 {
+                                html!{ <div class="hint">{format!("Exactly {} goals (push on the line): {:.1}%", line, 100.0 * gm.push)}</div> }
+                            } else { html!{} }}
+                            <label style="margin-top:12px;">{"Correct score (home down, away across)"}</label>
+                            <div style={format!("display:grid; grid-template-columns:repeat({}, 1fr); gap:4px; font-size:12px; text-align:center;", shown + 1)}>
+                                <div></div>
+                                { for (0..shown).map(|a| html!{ <div class="muted">{a}</div> }) }
+                                { for (0..shown).map(|h| html!{
+                                    <>
+                                        <div class="muted">{h}</div>
+                                        { for (0..shown).map(|a| {
+                                            let p = scores[h][a];
+                                            html!{
+                                                <div style={format!("padding:4px 0; border-radius:4px; background:rgba(99,102,241,{:.2});", (p * 4.0).min(0.6))}>
+                                                    {format!("{:.1}", 100.0 * p)}
+                                                </div>
+                                            }
+                                        }) }
+                                    </>
+                                }) }
+                            </div>
+                            <div class="hint">{format!("Other scores: {:.1}%", 100.0 * other)}</div>
+                            <div class="row" style="margin-top:12px; align-items:end;">
+                                <div class="input-group">
+                                    <label>{"Market for Single Bet"}</label>
+                                    <select onchange={on_pick}>
+                                        { for picks.iter().enumerate().map(|(i, (label, _, _))| html!{
+                                            <option selected={i == *poisson_pick}>{label.clone()}</option>
+                                        }) }
+                                    </select>
+                                </div>
+                                <button class="secondary" onclick={to_single}>{"→ Single Bet"}</button>
+                            </div>
+                            <div class="row">
+                                <button class="secondary" onclick={to_three}>{"Use in Three-Way"}</button>
+                                <button class="secondary" onclick={to_asian}>{"Use in Asian Handicap"}</button>
+                            </div>
+                            <div class="hint" style="margin-top:8px;">{"Single Bet takes Your % for the selected side and, on whole-number total lines, the push %. Asian Handicap takes Your % by goal difference."}</div>
+                        </>
+                    }
+                }}
+            </div>
+
+            <div class="card">
+                <h2>
+                    <span>{"Three-Way Market (Exact Kelly)"}</span>
+                    <span class={if (three_sum - 1.0).abs() < 1e-6 { "status-indicator success" } else { "status-indicator" }}>
+                        {format!("Total stake: {:.1}%", 100.0*three_sum)}
+                    </span>
+                </h2>
+                <div class="hint" style="margin-bottom:12px;">{"Enter market vs your probabilities for 3 mutually exclusive outcomes (e.g., Team A / Draw / Team B). This computes the exact Kelly allocation across outcomes."}</div>
+
+                { for (*three).iter().enumerate().map(|(i, r)| {
+                    let three_set = three.clone();
+                    let on_name = Callback::from(move |e: InputEvent| {
+                        let mut v = (*three_set).clone();
+                        let t: HtmlInputElement = e.target_unchecked_into();
+                        v[i].name = t.value();
+                        three_set.set(v);
+                    });
+                    let three_set2 = three.clone();
+                    let on_mkt = Callback::from(move |e: InputEvent| {
+                        let mut v = (*three_set2).clone();
+                        let t: HtmlInputElement = e.target_unchecked_into();
+                        v[i].mkt = t.value().parse::<f64>().unwrap_or(0.0).clamp(0.0, 100.0);
+                        three_set2.set(v);
+                    });
+                    let three_set3 = three.clone();
+                    let on_yours = Callback::from(move |e: InputEvent| {
+                        let mut v = (*three_set3).clone();
+                        let t: HtmlInputElement = e.target_unchecked_into();
+                        v[i].yours = t.value().parse::<f64>().unwrap_or(0.0).clamp(0.0, 100.0);
+                        three_set3.set(v);
+                    });
+                    html!{
+                        <div class="row three" style="gap:8px; margin-bottom:12px; align-items: end;">
+                            <div>
+                                <label>{"Outcome"}</label>
+                                <input value={r.name.clone()} oninput={on_name} aria-label="Three-way outcome name" />
+                            </div>
+                            <div>
+                                <label>{"Market %"}</label>
+                                <input type="number" min="0" max="100" step="0.1" value={format!("{:.1}", r.mkt)} oninput={on_mkt} aria-label="Three-way market probability" />
+                            </div>
+                            <div>
+                                <label>{"Your %"}</label>
+                                <input type="number" min="0" max="100" step="0.1" value={format!("{:.1}", r.yours)} oninput={on_yours} aria-label="Three-way your probability" />
+                            </div>
+                        </div>
+                    }
+                })}
+                <div class="section-divider"></div>
+                <div>
+                    { for three_rows.iter().enumerate().map(|(i, r)| {
+                        let frac = three_alloc.get(i).cloned().unwrap_or(0.0);
+                        html!{
+                            <div style="padding:8px; background:rgba(255,255,255,0.02); border-radius:6px; margin-top:6px;">
+                                { place_button(BetRecord::new(r.name.clone(), "Three-Way".into(), String::new(), d_vec[i], r.yours, bankroll_val()*frac, frac).with_market(r.mkt)) }
+                                <strong>{&r.name}</strong>
+                                <div style="font-size:12px; color: var(--muted); margin-top:2px;">
+                                    {format!("Recommend: {:.1}% of bankroll → ${:.0}", 100.0*frac, bankroll_val()*frac)}
+                                </div>
+                            </div>
+                        }
+                    }) }
+                </div>
+                <div class="hint" style="margin-top:12px;">{"Optimization: maximize expected log growth under sum of stakes ≤ 100%."}</div>
+            </div>
+
+            <div class="card">
+                <h2>
+                    <span>{"Exchange Market"}</span>
+                    <span class="status-indicator">{ if ex.market.is_empty() { "No market loaded".to_string() } else { format!("Market {}", ex.market) } }</span>
+                </h2>
+                <div class="hint" style="margin-bottom:12px;">{"Paste or load a market-book JSON snapshot. Each active runner can be backed or laid down its full price ladder; the exact Kelly allocation across runners fills the best prices first and only walks to worse levels while the marginal fill still adds log growth."}</div>
+                {{
+                    let exchange_text_set = exchange_text.clone();
+                    let on_text = Callback::from(move |e: InputEvent| {
+                        let t: web_sys::HtmlTextAreaElement = e.target_unchecked_into();
+                        exchange_text_set.set(t.value());
+                    });
+                    let (exchange_set, exchange_error_set) = (exchange.clone(), exchange_error.clone());
+                    let on_loaded = Callback::from(move |text: String| {
+                        match market_book_runners(&text, &exchange_set) {
+                            Ok(v) => { exchange_set.set(v); exchange_error_set.set(None); }
+                            Err(e) => exchange_error_set.set(Some(e)),
+                        }
+                    });
+                    let on_loaded2 = on_loaded.clone();
+                    let text = (*exchange_text).clone();
+                    let on_import = Callback::from(move |_| on_loaded2.emit(text.clone()));
+                    let reader = exchange_reader.clone();
+                    let on_file = Callback::from(move |e: Event| read_picked_file(e, reader.clone(), on_loaded.clone()));
+                    let exchange_set2 = exchange.clone();
+                    let on_commission = Callback::from(move |e: InputEvent| {
+                        let mut v = (*exchange_set2).clone();
+                        let t: HtmlInputElement = e.target_unchecked_into();
+                        v.commission = t.value().parse::<f64>().unwrap_or(0.0).clamp(0.0, 100.0);
+                        exchange_set2.set(v);
+                    });
+                    html!{
+                        <>
+                            <textarea rows="4" placeholder={"[{\"marketId\": ..., \"runners\": [{\"selectionId\": ..., \"ex\": {\"availableToBack\": [...], \"availableToLay\": [...]}}]}]"} value={(*exchange_text).clone()} oninput={on_text} aria-label="Market book JSON" />
+                            <div class="row" style="margin-top:8px;">
+                                <button class="secondary" onclick={on_import} disabled={exchange_text.trim().is_empty()}>{"Import Market Book"}</button>
+                                <div>
+                                    <label>{"Or load a file"}</label>
+                                    <input type="file" accept=".json,application/json" onchange={on_file} aria-label="Import market book file" />
+                                </div>
+                            </div>
+                            { match &*exchange_error {
+                                Some(e) => html!{ <div class="hint danger">{e}</div> },
+                                None => html!{},
+                            }}
+                            <div class="input-group" style="margin-top:12px;">
+                                <label>{"Commission on Net Winnings (%)"}</label>
+                                <input type="number" min="0" max="100" step="0.1" value={format!("{}", ex.commission)} oninput={on_commission} aria-label="Exchange commission" />
+                            </div>
+                        </>
+                    }
+                }}
+                { if ex.runners.is_empty() { html!{} } else { html!{
+                    <div class="muted" style="margin-bottom:8px;">
+                        {"Your % Sum: "}
+                        <span class={if (ex_your_sum - 100.0).abs() > 0.5 { "warning" } else { "success" }}>{format!("{:.1}%", ex_your_sum)}</span>
+                        { if (ex_your_sum - 100.0).abs() > 0.5 { html!{ <span class="warning">{" (normalized to 100%)"}</span> } } else { html!{} }}
+                    </div>
+                }}}
+                { for ex.runners.iter().zip(ex_calc.iter()).enumerate().map(|(i, (r, c))| {
+                    let exchange_set = exchange.clone();
+                    let on_yours = Callback::from(move |e: InputEvent| {
+                        let mut v = (*exchange_set).clone();
+                        let t: HtmlInputElement = e.target_unchecked_into();
+                        v.runners[i].yours = t.value().parse::<f64>().unwrap_or(0.0).clamp(0.0, 100.0);
+                        exchange_set.set(v);
+                    });
+                    let ladder = |l: &[(f64, f64)]| match l.first() {
+                        Some((price, size)) if l.len() > 1 => format!("{:.2} (${:.0}) +{} levels, ${:.0} total", price, size, l.len() - 1, l.iter().map(|x| x.1).sum::<f64>()),
+                        Some((price, size)) => format!("{:.2} (${:.0})", price, size),
+                        None => "—".into(),
+                    };
+                    let fill = |levels: usize, price: f64| if levels > 1 { format!(" across {} levels, avg {:.3}", levels, price) } else { format!(" @ {:.2}", price) };
+                    let capped = |on: bool| if on { " — capped by liquidity" } else { "" };
+                    html!{
+                        <div style="padding:8px; background:rgba(255,255,255,0.02); border-radius:6px; margin-top:6px;">
+                            <div class="row three">
+                                <div>
+                                    <strong>{&r.name}</strong>
+                                    <div style="font-size:12px; color: var(--muted);">{format!("Back {} | Lay {}", ladder(&r.back), ladder(&r.lay))}</div>
+                                </div>
+                                <div>
+                                    <label>{"Your %"}</label>
+                                    <input type="number" min="0" max="100" step="0.1" value={format!("{:.1}", r.yours)} oninput={on_yours} aria-label="Exchange runner your probability" />
+                                </div>
+                                <div style="font-size:12px;">
+                                    { if c.back_stake > 0.0 {
+                                        html!{ <div class="success">{format!("Back ${:.2}{} (EV {:+.1}%){}", c.back_stake, fill(c.back_levels, c.back_price), 100.0*c.back_ev, capped(c.back_capped))}</div> }
+                                    } else { html!{} }}
+                                    { if c.lay_stake > 0.0 {
+                                        html!{ <div class="success">{format!("Lay ${:.2}{}, liability ${:.2} (EV {:+.1}%){}", c.lay_stake, fill(c.lay_levels, c.lay_price), c.lay_liability, 100.0*c.lay_ev, capped(c.lay_capped))}</div> }
+                                    } else { html!{} }}
+                                    { if c.back_stake <= 0.0 && c.lay_stake <= 0.0 { html!{ <div class="muted">{"No bet"}</div> } } else { html!{} }}
+                                </div>
+                            </div>
+                            <div style="overflow:hidden;">
+                                { for exchange_bets(&ex, r, c).into_iter().flatten().map(&place_button) }
+                            </div>
+                        </div>
+                    }
+                }) }
+            </div>
+
+            <div class="card">
+                <h2>
+                    <span>{"Asian Handicap"}</span>
+                    { if ah_outcomes.is_empty() {
+                        html!{ <span class="status-indicator warning">{"Check inputs"}</span> }
+                    } else {
+                        html!{ <span class="status-indicator success">{format!("Kelly: {:.1}%", 100.0*ah_frac)}</span> }
+                    }}
+                </h2>
+                <div class="hint" style="margin-bottom:12px;">{"Quarter lines (e.g. -0.25, +0.75) split the stake across the two neighbouring lines. Enter your probability for each final goal difference (home − away)."}</div>
+                {{
+                    let asian_set = asian.clone();
+                    let on_side = Callback::from(move |e: Event| {
+                        let mut v = (*asian_set).clone();
+                        let t: HtmlSelectElement = e.target_unchecked_into();
+                        v.away = t.selected_index() > 0;
+                        asian_set.set(v);
+                    });
+                    let asian_set2 = asian.clone();
+                    let on_line = Callback::from(move |e: InputEvent| {
+                        let mut v = (*asian_set2).clone();
+                        let t: HtmlInputElement = e.target_unchecked_into();
+                        v.line = t.value();
+                        asian_set2.set(v);
+                    });
+                    let asian_set3 = asian.clone();
+                    let on_odds = Callback::from(move |e: InputEvent| {
+                        let mut v = (*asian_set3).clone();
+                        let t: HtmlInputElement = e.target_unchecked_into();
+                        v.odds = t.value();
+                        asian_set3.set(v);
+                    });
+                    html!{
+                        <div class="row three" style="gap:8px; margin-bottom:12px; align-items: end;">
+                            <div>
+                                <label>{"Side"}</label>
+                                <select onchange={on_side} aria-label="Asian handicap side">
+                                    <option selected={!ah.away}>{"Home"}</option>
+                                    <option selected={ah.away}>{"Away"}</option>
+                                </select>
+                            </div>
+                            <div>
+                                <label>{"Line"}</label>
+                                <input placeholder={"e.g. -0.25, +0.75"} value={ah.line.clone()} oninput={on_line}
+                                    class={if ah_line.is_some() { "" } else { "error" }} aria-label="Asian handicap line" />
+                            </div>
+                            <div>
+                                <label>{"Odds"}</label>
+                                <input placeholder={"e.g. 1.95, -105"} value={ah.odds.clone()} oninput={on_odds}
+                                    class={if ah_d.is_some() { "" } else { "error" }} aria-label="Asian handicap odds" />
+                            </div>
+                        </div>
+                    }
+                }}
+                <label>{"Your % by goal difference (home − away)"}</label>
+                <div style="display:grid; grid-template-columns:repeat(9, 1fr); gap:6px;">
+                    { for ah.dist.iter().enumerate().map(|(k, pct)| {
+                        let asian_set = asian.clone();
+                        let on_pct = Callback::from(move |e: InputEvent| {
+                            let mut v = (*asian_set).clone();
+                            let t: HtmlInputElement = e.target_unchecked_into();
+                            v.dist[k] = t.value().parse::<f64>().unwrap_or(0.0).clamp(0.0, 100.0);
+                            asian_set.set(v);
+                        });
+                        let gd = AH_MIN_GD + k as i32;
+                        let label = if k == 0 { format!("≤{}", gd) } else if k + 1 == ah.dist.len() { format!("≥+{}", gd) } else { format!("{:+}", gd) };
+                        html!{
+                            <div>
+                                <div class="hint" style="text-align:center;">{label}</div>
+                                <input type="number" min="0" max="100" step="0.1" style="padding:8px 4px;" value={format!("{:.1}", pct)} oninput={on_pct} aria-label="Goal difference probability" />
+                            </div>
+                        }
+                    }) }
+                </div>
+                <div class="hint">
+                    {"Sum: "}
+                    <span class={if (ah_total - 100.0).abs() > 0.5 { "warning" } else { "success" }}>{format!("{:.1}%", ah_total)}</span>
+                    { if (ah_total - 100.0).abs() > 0.5 { " (normalized to 100%)" } else { "" } }
+                </div>
+                <div class="section-divider"></div>
+                { for ah_outcomes.iter().filter(|o| o.1 > 0.0).map(|(name, prob, ret)| {
+                    html!{
+                        <div style="padding:8px; background:rgba(255,255,255,0.02); border-radius:6px; margin-top:6px;">
+                            <strong>{*name}</strong>
+                            <div style="font-size:12px; color: var(--muted); margin-top:2px;">
+                                {format!("Probability {:.1}% | Returns {:.3} per $1", 100.0*prob, ret)}
+                            </div>
+                        </div>
+                    }
+                }) }
+                <div class="metric-grid">
+                    <div class="metric-item">
+                        <div class={format!("metric-value {}", if ah_ev.is_nan() { "muted" } else if ah_ev > 0.0 { "success" } else { "danger" })}>
+                            { if ah_ev.is_nan() { "—".into() } else { format!("{:+.3}", ah_ev) }}
+                        </div>
+                        <div class="metric-label">{"EV per $1"}</div>
+                    </div>
+                    <div class="metric-item">
+                        <div class="metric-value">{format!("${:.0}", bankroll_val()*ah_frac)}</div>
+                        <div class="metric-label">{"Kelly Stake"}</div>
+                    </div>
+                </div>
+                <div class="hint" style="margin-top:12px;">{"Optimization: maximize expected log growth over the settled outcomes."}</div>
+            </div>
+
+            <div class="card">
+                <h2>
+                    <span>{"Cash-Out Offer"}</span>
+                    { match co_calc {
+                        Some(c) if c.alpha < 0.02 => html!{ <span class="status-indicator danger">{"Reject"}</span> },
+                        Some(c) if c.alpha > 0.98 => html!{ <span class="status-indicator success">{"Accept"}</span> },
+                        Some(_) => html!{ <span class="status-indicator warning">{"Partial cash-out"}</span> },
+                        None => html!{ <span class="status-indicator warning">{"Check inputs"}</span> },
+                    }}
+                </h2>
+                <div class="hint" style="margin-bottom:12px;">{"Compares keeping the bet, cashing out, or cashing out part of it by expected log growth of your total bankroll (bankroll excludes the open stake)."}</div>
+                {{
+                    let cashout_set = cashout.clone();
+                    let on_stake = Callback::from(move |e: InputEvent| {
+                        let mut v = (*cashout_set).clone();
+                        let t: HtmlInputElement = e.target_unchecked_into();
+                        v.stake = t.value();
+                        cashout_set.set(v);
+                    });
+                    let cashout_set2 = cashout.clone();
+                    let on_odds = Callback::from(move |e: InputEvent| {
+                        let mut v = (*cashout_set2).clone();
+                        let t: HtmlInputElement = e.target_unchecked_into();
+                        v.odds = t.value();
+                        cashout_set2.set(v);
+                    });
+                    let cashout_set3 = cashout.clone();
+                    let on_prob = Callback::from(move |e: InputEvent| {
+                        let mut v = (*cashout_set3).clone();
+                        let t: HtmlInputElement = e.target_unchecked_into();
+                        v.prob = t.value().parse::<f64>().unwrap_or(0.0).clamp(0.0, 100.0);
+                        cashout_set3.set(v);
+                    });
+                    let cashout_set4 = cashout.clone();
+                    let on_offer = Callback::from(move |e: InputEvent| {
+                        let mut v = (*cashout_set4).clone();
+                        let t: HtmlInputElement = e.target_unchecked_into();
+                        v.offer = t.value();
+                        cashout_set4.set(v);
+                    });
+                    html!{
+                        <div class="row four" style="gap:8px; margin-bottom:12px; align-items: end;">
+                            <div>
+                                <label>{"Original Stake ($)"}</label>
+                                <input value={co.stake.clone()} oninput={on_stake}
+                                    class={if co_stake.is_some() { "" } else { "error" }} aria-label="Cash-out original stake" />
+                            </div>
+                            <div>
+                                <label>{"Odds Taken"}</label>
+                                <input placeholder={"e.g. 3.0, +200"} value={co.odds.clone()} oninput={on_odds}
+                                    class={if co_d.is_some() { "" } else { "error" }} aria-label="Cash-out odds taken" />
+                            </div>
+                            <div>
+                                <label>{"Win % Now"}</label>
+                                <input type="number" min="0" max="100" step="0.1" value={format!("{:.1}", co.prob)} oninput={on_prob} aria-label="Cash-out current win probability" />
+                            </div>
+                            <div>
+                                <label>{"Offer ($)"}</label>
+                                <input value={co.offer.clone()} oninput={on_offer}
+                                    class={if co_offer.is_some() { "" } else { "error" }} aria-label="Cash-out offer" />
+                            </div>
+                        </div>
+                    }
+                }}
+                { if let Some(c) = co_calc {
+                    html!{
+                        <>
+                            <div class="metric-grid">
+                                <div class="metric-item">
+                                    <div class="metric-value">{format!("{:.0}% → ${:.0}", 100.0*c.alpha, c.alpha * co_offer.unwrap_or(0.0))}</div>
+                                    <div class="metric-label">{"Optimal Cash-Out"}</div>
+                                </div>
+                                <div class="metric-item">
+                                    <div class="metric-value">{format!("${:.2}", c.fair_offer)}</div>
+                                    <div class="metric-label">{"Break-Even Offer"}</div>
+                                </div>
+                                <div class="metric-item">
+                                    <div class="metric-value">{format!("${:.2}", c.hold_ev)}</div>
+                                    <div class="metric-label">{"EV of Holding"}</div>
+                                </div>
+                                <div class="metric-item">
+                                    <div class="metric-value">{format!("{:+.1} | {:+.1} | {:+.1} bp", 10_000.0*c.g_hold, 10_000.0*c.g_accept, 10_000.0*c.g_best)}</div>
+                                    <div class="metric-label">{"Log Growth: Hold | Accept | Optimal"}</div>
+                                </div>
+                            </div>
+                            <div class="hint" style="margin-top:12px;">{"Break-even offer: the certain amount worth the same log growth as holding the whole bet."}</div>
+                        </>
+                    }
+                } else { html!{} }}
+            </div>
+
+            <div class="card">
+                <h2>
+                    <span>{"Each-Way Bet"}</span>
+                    { if ew_valid && ew_win_d.is_some() {
+                        html!{ <span class="status-indicator success">{"Valid"}</span> }
+                    } else {
+                        html!{ <span class="status-indicator warning">{"Check inputs"}</span> }
+                    }}
+                </h2>
+                <div class="hint" style="margin-bottom:12px;">{"Win part + place part at a fraction of the win odds. Your place % is the chance of finishing in the places (including winning)."}</div>
+                {{
+                    let each_way_set = each_way.clone();
+                    let on_odds = Callback::from(move |e: InputEvent| {
+                        let mut v = (*each_way_set).clone();
+                        let t: HtmlInputElement = e.target_unchecked_into();
+                        v.odds = t.value();
+                        each_way_set.set(v);
+                    });
+                    let each_way_set2 = each_way.clone();
+                    let on_terms = Callback::from(move |e: Event| {
+                        let mut v = (*each_way_set2).clone();
+                        let t: HtmlSelectElement = e.target_unchecked_into();
+                        v.terms = if t.selected_index() == 0 { 4 } else { 5 };
+                        each_way_set2.set(v);
+                    });
+                    let each_way_set3 = each_way.clone();
+                    let on_places = Callback::from(move |e: InputEvent| {
+                        let mut v = (*each_way_set3).clone();
+                        let t: HtmlInputElement = e.target_unchecked_into();
+                        v.places = t.value().parse::<u32>().unwrap_or(1).clamp(1, 10);
+                        each_way_set3.set(v);
+                    });
+                    let each_way_set4 = each_way.clone();
+                    let on_win = Callback::from(move |e: InputEvent| {
+                        let mut v = (*each_way_set4).clone();
+                        let t: HtmlInputElement = e.target_unchecked_into();
+                        v.win = t.value().parse::<f64>().unwrap_or(0.0).clamp(0.0, 100.0);
+                        each_way_set4.set(v);
+                    });
+                    let each_way_set5 = each_way.clone();
+                    let on_place = Callback::from(move |e: InputEvent| {
+                        let mut v = (*each_way_set5).clone();
+                        let t: HtmlInputElement = e.target_unchecked_into();
+                        v.place = t.value().parse::<f64>().unwrap_or(0.0).clamp(0.0, 100.0);
+                        each_way_set5.set(v);
+                    });
+                    html!{
+                        <div class="row three" style="gap:8px; margin-bottom:12px; align-items: end;">
+                            <div>
+                                <label>{"Win Odds"}</label>
+                                <input placeholder={"e.g. 8.0, +700, 7/1"} value={ew.odds.clone()} oninput={on_odds}
+                                    class={if ew_win_d.is_some() { "" } else { "error" }} aria-label="Each-way win odds" />
+                            </div>
+                            <div>
+                                <label>{"Place Terms"}</label>
+                                <select onchange={on_terms} aria-label="Each-way place terms">
+                                    <option selected={ew.terms == 4}>{"1/4 odds"}</option>
+                                    <option selected={ew.terms == 5}>{"1/5 odds"}</option>
+                                </select>
+                            </div>
+                            <div>
+                                <label>{"Places"}</label>
+                                <input type="number" min="1" max="10" step="1" value={ew.places.to_string()} oninput={on_places} aria-label="Each-way places paid" />
+                            </div>
+                            <div>
+                                <label>{"Your Win %"}</label>
+                                <input type="number" min="0" max="100" step="0.1" value={format!("{:.1}", ew.win)} oninput={on_win} aria-label="Each-way your win probability" />
+                            </div>
+                            <div>
+                                <label>{format!("Your Top-{} %", ew.places)}</label>
+                                <input type="number" min="0" max="100" step="0.1" value={format!("{:.1}", ew.place)} oninput={on_place}
+                                    class={if ew_valid { "" } else { "error" }} aria-label="Each-way your place probability" />
+                            </div>
+                        </div>
+                    }
+                }}
+                { if !ew_valid {
+                    html!{ <div class="hint warning">{"Place % must be at least the win %"}</div> }
+                } else { html!{} }}
+                <div class="section-divider"></div>
+                <div class="metric-grid">
+                    <div class="metric-item">
+                        <div class="metric-value">{ ew_place_d.map(|d| format!("{:.3}", d)).unwrap_or_else(|| "—".into()) }</div>
+                        <div class="metric-label">{"Place Odds"}</div>
+                    </div>
+                    <div class="metric-item">
+                        <div class="metric-value">{format!("{:.1}% | {:.1}% | {:.1}%", 100.0*ew_pw, 100.0*ew_pp, 100.0*ew_pl)}</div>
+                        <div class="metric-label">{"Win | Place only | Lose"}</div>
+                    </div>
+                    <div class="metric-item">
+                        <div class={format!("metric-value {}", if ew_ev.is_nan() { "muted" } else if ew_ev > 0.0 { "success" } else { "danger" })}>
+                            { if ew_ev.is_nan() { "—".into() } else { format!("{:+.3}", ew_ev) }}
+                        </div>
+                        <div class="metric-label">{"EV per $1 (total stake)"}</div>
+                    </div>
+                    <div class="metric-item">
+                        <div class={format!("metric-value {}", if ew_frac > 0.0 { "success" } else { "danger" })}>
+                            {format!("{:.2}%", 100.0*ew_frac)}
+                        </div>
+                        <div class="metric-label">{"Kelly (Total Stake)"}</div>
+                    </div>
+                </div>
+                <div class="hint" style="margin-top:12px;">
+                    { if ew_frac > 0.0 {
+                        format!("Stake ${:.0} total → ${:.0} each way (win + place)", bankroll_val()*ew_frac, bankroll_val()*ew_frac/2.0)
+                    } else {
+                        "No each-way edge. Kelly suggests no bet.".to_string()
+                    }}
+                </div>
+            </div>
+
+            <div class="card">
+                <h2>
+                    <span>{"Export Recommendations"}</span>
+                    <span class="status-indicator">{format!("{} bets", recommendations.len())}</span>
+                </h2>
+                <div class="hint" style="margin-bottom:12px;">{"Every panel's bets with a positive recommended stake: selection, odds in all formats, your probability, Kelly %, stake and EV per $1."}</div>
+                {{
+                    let recs_csv = recommendations.clone();
+                    let on_csv = Callback::from(move |_| download_file("edgerunner-recommendations.csv", "text/csv", &recommendations_csv(&recs_csv)));
+                    let recs_json = recommendations.clone();
+                    let on_json = Callback::from(move |_| download_file("edgerunner-recommendations.json", "application/json", &recommendations_json(&recs_json)));
+                    html!{
+                        <div class="row">
+                            <button class="secondary" onclick={on_csv} disabled={recommendations.is_empty()}>{"Export CSV"}</button>
+                            <button class="secondary" onclick={on_json} disabled={recommendations.is_empty()}>{"Export JSON"}</button>
+                        </div>
+                    }
+                }}
+                { for recommendations.iter().map(|r| html!{
+                    <div style="padding:8px; background:rgba(255,255,255,0.02); border-radius:6px; margin-top:6px;">
+                        <strong>{&r.selection}</strong><span class="muted">{format!(" — {}", r.panel)}</span>
+                        <div style="font-size:12px; color: var(--muted); margin-top:2px;">
+                            {format!("{:.3} | {} | {} — {:.1}% — Kelly {:.1}% → ${:.2}", r.odds_decimal, r.odds_american, r.odds_fractional, r.prob_pct, r.kelly_pct, r.stake)}
+                        </div>
+                    </div>
+                }) }
+            </div>
+
+            <div class="card">
+                <h2>
+                    <span>{"Presets"}</span>
+                    <span class="status-indicator">{format!("{} saved", presets.len())}</span>
+                </h2>
+                <div class="hint" style="margin-bottom:12px;">{"Save the current inputs of one panel or all of them under a name; only an all-panels preset changes the bankroll. Saving an existing name replaces it. Export/import JSON to share a preset library."}</div>
+                {{
+                    let preset_name_set = preset_name.clone();
+                    let on_name = Callback::from(move |e: InputEvent| {
+                        let t: HtmlInputElement = e.target_unchecked_into();
+                        preset_name_set.set(t.value());
+                    });
+                    let preset_scope_set = preset_scope.clone();
+                    let on_scope = Callback::from(move |e: Event| {
+                        let t: HtmlSelectElement = e.target_unchecked_into();
+                        if let Some((s, _)) = PresetScope::all().get(t.selected_index().max(0) as usize) { preset_scope_set.set(*s); }
+                    });
+                    let presets_set = presets.clone();
+                    let preset_name_clear = preset_name.clone();
+                    let (name, scope, state) = (preset_name.trim().to_string(), *preset_scope, app_state.clone());
+                    let on_save = Callback::from(move |_| {
+                        if name.is_empty() { return; }
+                        let mut v: Vec<Preset> = (*presets_set).iter().filter(|p| p.name != name).cloned().collect();
+                        v.push(Preset { name: name.clone(), scope, state: state.clone() });
+                        v.sort_by_key(|p| p.name.to_lowercase());
+                        presets_set.set(v);
+                        preset_name_clear.set(String::new());
+                    });
+                    let presets_export = (*presets).clone();
+                    let on_export = Callback::from(move |_| {
+                        if let Ok(json) = serde_json::to_string_pretty(&presets_export) {
+                            download_file("edgerunner-presets.json", "application/json", &json);
+                        }
+                    });
+                    let (presets_set2, presets_msg_set) = (presets.clone(), presets_msg.clone());
+                    let on_loaded = Callback::from(move |text: String| {
+                        match serde_json::from_str::<Vec<Preset>>(&text) {
+                            Ok(imported) => {
+                                // merge by name, imported presets win
+                                let n = imported.len();
+                                let mut v: Vec<Preset> = (*presets_set2).iter().filter(|p| !imported.iter().any(|i| i.name == p.name)).cloned().collect();
+                                v.extend(imported);
+                                v.sort_by_key(|p| p.name.to_lowercase());
+                                presets_set2.set(v);
+                                presets_msg_set.set(Some(Ok(format!("Imported {} presets.", n))));
+                            }
+                            Err(e) => presets_msg_set.set(Some(Err(format!("Not a preset export: {}", e)))),
+                        }
+                    });
+                    let reader = presets_reader.clone();
+                    let on_import = Callback::from(move |e: Event| read_picked_file(e, reader.clone(), on_loaded.clone()));
+                    html!{
+                        <>
+                            <div class="row">
+                                <div class="input-group">
+                                    <label>{"Name"}</label>
+                                    <input placeholder={"e.g. NFL sides, half Kelly"} value={(*preset_name).clone()} oninput={on_name} aria-label="Preset name" />
+                                </div>
+                                <div class="input-group">
+                                    <label>{"Panel"}</label>
+                                    <select onchange={on_scope} aria-label="Preset panel">
+                                        { for PresetScope::all().iter().map(|(s, label)| html!{ <option selected={*s == *preset_scope}>{ *label }</option> }) }
+                                    </select>
+                                </div>
+                            </div>
+                            <button onclick={on_save} disabled={preset_name.trim().is_empty()}>{"Save Preset"}</button>
+                            <div class="row" style="margin:12px 0;">
+                                <button class="secondary" onclick={on_export}>{"Export JSON"}</button>
+                                <div>
+                                    <label>{"Import JSON"}</label>
+                                    <input type="file" accept=".json,application/json" onchange={on_import} aria-label="Import presets JSON" />
+                                </div>
+                            </div>
+                        </>
+                    }
+                }}
+                { match &*presets_msg {
+                    Some(Ok(m)) => html!{ <div class="hint" style="margin-bottom:12px;">{m}</div> },
+                    Some(Err(e)) => html!{ <div class="hint danger" style="margin-bottom:12px;">{e}</div> },
+                    None => html!{},
+                }}
+                { for presets.iter().enumerate().map(|(i, p)| {
+                    let apply_state = apply_state.clone();
+                    let (current, preset) = (app_state.clone(), p.clone());
+                    let on_load = Callback::from(move |_| apply_state.emit(preset.scope.merge(&current, &preset.state)));
+                    let presets_set = presets.clone();
+                    let on_delete = Callback::from(move |_| {
+                        let mut v = (*presets_set).clone();
+                        if i < v.len() { v.remove(i); }
+                        presets_set.set(v);
+                    });
+                    html!{
+                        <div style="padding:8px; background:rgba(255,255,255,0.02); border-radius:6px; margin-top:6px;">
+                            <div style="float:right; display:flex; gap:6px;">
+                                <button class="small" onclick={on_load}>{"Load"}</button>
+                                <button class="danger small" onclick={on_delete}>{"Delete"}</button>
+                            </div>
+                            <strong>{&p.name}</strong>
+                            <div style="font-size:12px; color: var(--muted); margin-top:2px;">{p.scope.label()}</div>
+                        </div>
+                    }
+                }) }
+            </div>
+
+            <div class="card">
+                <h2>
+                    <span>{"Bet Journal"}</span>
+                    <span class="status-indicator">{format!("{} open / {} total", bets.iter().filter(|b| b.status == BetStatus::Open).count(), bets.len())}</span>
+                </h2>
+                <div class="hint" style="margin-bottom:12px;">{"\"Place\" on any recommendation records it here. Bets are saved in this browser; export/import JSON to back them up or move them."}</div>
+                {{
+                    let bets_export = (*bets).clone();
+                    let on_export = Callback::from(move |_| {
+                        if let Ok(json) = serde_json::to_string_pretty(&bets_export) {
+                            download_file("edgerunner-bets.json", "application/json", &json);
+                        }
+                    });
+                    let (bets_set, bets_msg_set) = (bets.clone(), bets_msg.clone());
+                    let on_loaded = Callback::from(move |text: String| {
+                        match serde_json::from_str::<Vec<BetRecord>>(&text) {
+                            Ok(imported) => {
+                                let mut v = (*bets_set).clone();
+                                let (added, updated) = merge_bets(&mut v, imported);
+                                bets_set.set(v);
+                                bets_msg_set.set(Some(Ok(format!("Imported {} new bets, updated {} already in the journal.", added, updated))));
+                            }
+                            Err(e) => bets_msg_set.set(Some(Err(format!("Not a bet journal export: {}", e)))),
+                        }
+                    });
+                    let reader = bets_reader.clone();
+                    let on_import = Callback::from(move |e: Event| read_picked_file(e, reader.clone(), on_loaded.clone()));
+                    html!{
+                        <div class="row" style="margin-bottom:12px;">
+                            <button class="secondary" onclick={on_export}>{"Export JSON"}</button>
+                            <div>
+                                <label>{"Import JSON"}</label>
+                                <input type="file" accept=".json,application/json" onchange={on_import} aria-label="Import bets JSON" />
+                            </div>
+                        </div>
+                    }
+                }}
+                { match &*bets_msg {
+                    Some(Ok(m)) => html!{ <div class="hint" style="margin-bottom:12px;">{m}</div> },
+                    Some(Err(e)) => html!{ <div class="hint danger" style="margin-bottom:12px;">{e}</div> },
+                    None => html!{},
+                }}
+                { for bets.iter().enumerate().rev().map(|(i, b)| {
+                    let settle = |status: BetStatus| {
+                        let bets_set = bets.clone();
+                        let onclick = Callback::from(move |_| {
+                            let mut v = (*bets_set).clone();
+                            v[i].settle(status, js_sys::Date::now());
+                            bets_set.set(v);
+                        });
+                        let class = if b.status == status { "small" } else { "secondary small" };
+                        html!{ <button {class} {onclick}>{status.label()}</button> }
+                    };
+                    let bets_set = bets.clone();
+                    let on_stake = Callback::from(move |e: InputEvent| {
+                        let mut v = (*bets_set).clone();
+                        let t: HtmlInputElement = e.target_unchecked_into();
+                        v[i].stake = t.value().parse::<f64>().unwrap_or(0.0).max(0.0);
+                        bets_set.set(v);
+                    });
+                    let bets_set3 = bets.clone();
+                    let on_closing = Callback::from(move |e: InputEvent| {
+                        let mut v = (*bets_set3).clone();
+                        let t: HtmlInputElement = e.target_unchecked_into();
+                        v[i].closing = t.value();
+                        bets_set3.set(v);
+                    });
+                    let bets_set4 = bets.clone();
+                    let on_closing_others = Callback::from(move |e: InputEvent| {
+                        let mut v = (*bets_set4).clone();
+                        let t: HtmlInputElement = e.target_unchecked_into();
+                        v[i].closing_others = t.value();
+                        bets_set4.set(v);
+                    });
+                    let bets_set2 = bets.clone();
+                    let on_delete = Callback::from(move |_| {
+                        let mut v = (*bets_set2).clone();
+                        if i < v.len() { v.remove(i); }
+                        bets_set2.set(v);
+                    });
+                    html!{
+                        <div style="padding:8px; background:rgba(255,255,255,0.02); border-radius:6px; margin-top:6px;">
+                            <strong>{&b.selection}</strong>{" — "}{&b.market}
+                            { if b.book.trim().is_empty() { html!{} } else { html!{ <span class="muted">{format!(" @ {}", b.book)}</span> } }}
+                            <div style="font-size:12px; color: var(--muted); margin-top:2px;">
+                                { if b.lay {
+                                    format!("{} UTC | Lay {:.3}, liability ${:.2} | Your {:.1}% | Kelly {:.1}%", format_timestamp(b.placed_at), b.odds, b.risk(), b.prob, 100.0*b.kelly)
+                                } else {
+                                    format!("{} UTC | Odds {:.3} | Your {:.1}% | Kelly {:.1}%", format_timestamp(b.placed_at), b.odds, b.prob, 100.0*b.kelly)
+                                }}
+                                { match b.profit() {
+                                    Some(pl) => html!{ <span class={if pl > 0.0 { "success" } else if pl < 0.0 { "danger" } else { "" }}>{format!(" | P&L {:+.2}", pl)}</span> },
+                                    None => html!{},
+                                }}
+                            </div>
+                            <div class="row" style="grid-template-columns: 120px 1fr; margin-top:6px; align-items:center;">
+                                <input type="number" min="0" step="1" style="padding:6px 8px;" value={format!("{:.2}", b.stake)} oninput={on_stake} aria-label="Bet stake" />
+                                <div style="display:flex; gap:6px; flex-wrap:wrap;">
+                                    { settle(BetStatus::Open) }
+                                    { settle(BetStatus::Won) }
+                                    { settle(BetStatus::Lost) }
+                                    { settle(BetStatus::Push) }
+                                    { settle(BetStatus::Void) }
+                                    <button class="danger small" onclick={on_delete}>{"Delete"}</button>
+                                </div>
+                            </div>
+                            <div class="row" style="margin-top:6px;">
+                                <input placeholder={"Closing odds"} style="padding:6px 8px;" value={b.closing.clone()} oninput={on_closing}
+                                    class={if b.closing.trim().is_empty() || parse_any(&b.closing).is_some() { "" } else { "error" }} aria-label="Closing odds" />
+                                <input placeholder={"Other sides at close, e.g. 1.95, 3.40"} style="padding:6px 8px;" value={b.closing_others.clone()} oninput={on_closing_others} aria-label="Closing odds of other outcomes" />
+                            </div>
+                            { match bet_clv(b, journal.devig) {
+                                Some((clv_odds, clv_prob)) => html!{
+                                    <div class={if clv_prob > 0.0 { "hint success" } else { "hint danger" }}>
+                                        {format!("CLV {:+.2}% odds | {:+.2}% prob", 100.0*clv_odds, 100.0*clv_prob)}
+                                    </div>
+                                },
+                                None => html!{},
+                            }}
+                        </div>
+                    }
+                }) }
+                { if bets.is_empty() {
+                    html!{ <div class="hint">{"No bets recorded yet."}</div> }
+                } else { html!{} }}
+            </div>
+
+            <div class="card">
+                <h2>
+                    <span>{"Performance"}</span>
+                    <span class={format!("status-indicator {}", if stats.pnl > 0.0 { "success" } else if stats.pnl < 0.0 { "danger" } else { "" })}>
+                        {format!("P&L {:+.2}", stats.pnl)}
+                    </span>
+                </h2>
+                {{
+                    let journal_set = journal.clone();
+                    let on_start = Callback::from(move |e: InputEvent| {
+                        let mut v = (*journal_set).clone();
+                        let t: HtmlInputElement = e.target_unchecked_into();
+                        v.start_bank = t.value().trim().replace(',', "").parse::<f64>().unwrap_or(0.0).max(0.0);
+                        journal_set.set(v);
+                    });
+                    let journal_set2 = journal.clone();
+                    let on_auto = Callback::from(move |e: Event| {
+                        let mut v = (*journal_set2).clone();
+                        let t: HtmlInputElement = e.target_unchecked_into();
+                        v.auto_bankroll = t.checked();
+                        journal_set2.set(v);
+                    });
+                    html!{
+                        <div class="row" style="align-items:end;">
+                            <div class="input-group">
+                                <label>{"Starting Bankroll ($)"}</label>
+                                <input type="number" min="0" step="1" value={format!("{:.2}", journal.start_bank)} oninput={on_start} aria-label="Starting bankroll" />
+                            </div>
+                            <div class="input-group">
+                                <label class="tooltip" data-tooltip="Keep the bankroll input at start + settled P&L - open stakes">
+                                    <input type="checkbox" style="width:auto; margin-right:6px;" checked={journal.auto_bankroll} onchange={on_auto} aria-label="Auto-update bankroll" />
+                                    {"Auto-update bankroll"}
+                                </label>
+                                <div class="hint">{format!("Current: ${:.2} (${:.2} open)", current_bank, stats.open_stake)}</div>
+                            </div>
+                        </div>
+                    }
+                }}
+                <div class="metric-grid">
+                    <div class="metric-item">
+                        <div class="metric-value">{format!("${:.2}", stats.turnover)}</div>
+                        <div class="metric-label">{format!("Turnover ({} settled)", stats.settled)}</div>
+                    </div>
+                    <div class="metric-item">
+                        <div class={format!("metric-value {}", if stats.pnl >= 0.0 { "success" } else { "danger" })}>
+                            { if stats.turnover > 0.0 { format!("{:+.2}%", 100.0*stats.pnl/stats.turnover) } else { "—".into() }}
+                        </div>
+                        <div class="metric-label">{"Yield (P&L / Turnover)"}</div>
+                    </div>
+                    <div class="metric-item">
+                        <div class={format!("metric-value {}", if stats.pnl >= 0.0 { "success" } else { "danger" })}>
+                            { if journal.start_bank > 0.0 { format!("{:+.2}%", 100.0*stats.pnl/journal.start_bank) } else { "—".into() }}
+                        </div>
+                        <div class="metric-label">{"ROI (P&L / Starting Bankroll)"}</div>
+                    </div>
+                    <div class="metric-item">
+                        {{
+                            let (bets_n, wins): (usize, usize) = stats.bands.iter().fold((0, 0), |acc, b| (acc.0 + b.bets, acc.1 + b.wins));
+                            html!{ <div class="metric-value">{ if bets_n > 0 { format!("{:.1}%", 100.0 * wins as f64 / bets_n as f64) } else { "—".into() }}</div> }
+                        }}
+                        <div class="metric-label">{"Win Rate"}</div>
+                    </div>
+                </div>
+                <div class="section-divider"></div>
+                <div class="muted" style="margin-bottom:8px;">{"Bankroll Over Time"}</div>
+                {{
+                    let h = &stats.history;
+                    let lo = h.iter().cloned().fold(f64::INFINITY, f64::min);
+                    let hi = h.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+                    let span = if hi - lo > 1e-9 { hi - lo } else { 1.0 };
+                    let step = if h.len() > 1 { 300.0 / (h.len() - 1) as f64 } else { 0.0 };
+                    let points = h.iter().enumerate()
+                        .map(|(i, v)| format!("{:.1},{:.1}", i as f64 * step, 95.0 - 90.0 * (v - lo) / span))
+                        .collect::<Vec<_>>().join(" ");
+                    html!{
+                        <>
+                            <svg viewBox="0 0 300 100" preserveAspectRatio="none" style="width:100%; height:140px; background:rgba(255,255,255,0.02); border-radius:8px;">
+                                <polyline points={points} fill="none" stroke="var(--accent)" stroke-width="2" vector-effect="non-scaling-stroke" />
+                            </svg>
+                            <div class="hint">{format!("Low ${:.2} · High ${:.2} · {} settlements", lo, hi, h.len() - 1)}</div>
+                        </>
+                    }
+                }}
+                <div class="section-divider"></div>
+                <div class="muted" style="margin-bottom:8px;">{"Win Rate by Odds Band"}</div>
+                { for stats.bands.iter().filter(|b| b.bets > 0).map(|b| html!{
+                    <div style="padding:8px; background:rgba(255,255,255,0.02); border-radius:6px; margin-top:6px;">
+                        <strong>{b.label}</strong>
+                        <div style="font-size:12px; color: var(--muted); margin-top:2px;">
+                            {format!("{} bets | won {:.1}% vs implied {:.1}% | P&L {:+.2}",
+                                b.bets, 100.0 * b.wins as f64 / b.bets as f64, 100.0 * b.implied / b.bets as f64, b.pnl)}
+                        </div>
+                    </div>
+                }) }
+                { if stats.settled == 0 {
+                    html!{ <div class="hint">{"Settle bets in the journal to see performance."}</div> }
+                } else { html!{} }}
+            </div>
+
+            <div class="card">
+                <h2>
+                    <span>{"Calibration"}</span>
+                    <span class={format!("status-indicator {}", if calib_yours.n == 0 { "" } else if calib_yours.brier < calib_market.brier { "success" } else { "warning" })}>
+                        { if calib_yours.n == 0 { "No settled bets".to_string() }
+                          else if calib_yours.brier < calib_market.brier { "Beating the market".to_string() }
+                          else { "Market is sharper".to_string() } }
+                    </span>
+                </h2>
+                <div class="hint" style="margin-bottom:12px;">{"Your % vs the market % recorded at bet time, over won/lost bets (push and void excluded). Lower Brier score and log loss are better."}</div>
+                <div class="metric-grid">
+                    <div class="metric-item">
+                        <div class="metric-value">{ if calib_yours.n == 0 { "—".into() } else { format!("{:.4} | {:.4}", calib_yours.brier, calib_market.brier) }}</div>
+                        <div class="metric-label">{"Brier — Yours | Market"}</div>
+                    </div>
+                    <div class="metric-item">
+                        <div class="metric-value">{ if calib_yours.n == 0 { "—".into() } else { format!("{:.4} | {:.4}", calib_yours.log_loss, calib_market.log_loss) }}</div>
+                        <div class="metric-label">{"Log Loss — Yours | Market"}</div>
+                    </div>
+                </div>
+                { if calib_yours.n == 0 {
+                    html!{ <div class="hint">{"Settle bets as won or lost to see calibration."}</div> }
+                } else {
+                    let dots = |c: &Calibration, color: &'static str| c.bins.iter().filter(|b| b.0 > 0).map(|b| html!{
+                        <circle cx={format!("{:.2}", 100.0*b.1)} cy={format!("{:.2}", 100.0 - 100.0*b.2)} r="2.5" fill={color} />
+                    }).collect::<Html>();
+                    html!{
+                        <>
+                            <div class="section-divider"></div>
+                            <div class="muted" style="margin-bottom:8px;">{"Reliability Diagram (predicted vs realized)"}</div>
+                            <svg viewBox="-4 -4 108 108" style="width:100%; max-width:260px; display:block; margin:0 auto; background:rgba(255,255,255,0.02); border-radius:8px;">
+                                <rect x="0" y="0" width="100" height="100" fill="none" stroke="var(--border)" />
+                                <line x1="0" y1="100" x2="100" y2="0" stroke="var(--muted)" stroke-dasharray="2 2" />
+                                { dots(&calib_market, "var(--accent-2)") }
+                                { dots(&calib_yours, "var(--accent)") }
+                            </svg>
+                            <div class="hint" style="text-align:center;">
+                                <span style="color: var(--accent);">{"● Yours"}</span>{"  "}<span style="color: var(--accent-2);">{"● Market"}</span>{" — dashed line is perfect calibration"}
+                            </div>
+                            { for calib_yours.bins.iter().zip(calib_market.bins.iter()).enumerate().filter(|(_, (y, m))| y.0 > 0 || m.0 > 0).map(|(i, (y, m))| html!{
+                                <div style="padding:8px; background:rgba(255,255,255,0.02); border-radius:6px; margin-top:6px;">
+                                    <strong>{format!("{}–{}%", 100 * i / CALIBRATION_BINS, 100 * (i + 1) / CALIBRATION_BINS)}</strong>
+                                    <div style="font-size:12px; color: var(--muted); margin-top:2px;">
+                                        {format!("Yours: {} bets, predicted {:.1}% → won {:.1}% | Market: {} bets, predicted {:.1}% → won {:.1}%",
+                                            y.0, 100.0*y.1, 100.0*y.2, m.0, 100.0*m.1, 100.0*m.2)}
+                                    </div>
+                                </div>
+                            }) }
+                        </>
+                    }
+                }}
+            </div>
+
+            <div class="card">
+                <h2>
+                    <span>{"Closing Line Value"}</span>
+                    <span class={format!("status-indicator {}", if clv_rows.is_empty() { "" } else if clv_prob_avg > 0.0 { "success" } else { "danger" })}>
+                        {format!("{} bets with closing odds", clv_rows.len())}
+                    </span>
+                </h2>
+                <div class="hint" style="margin-bottom:12px;">{"Record closing odds in the journal. Odds CLV = odds taken / closing odds − 1; probability CLV = fair closing probability − implied probability at the odds taken. Enter the other sides' closing odds to remove the margin."}</div>
+                {{
+                    let journal_set = journal.clone();
+                    let on_devig = Callback::from(move |e: Event| {
+                        let mut v = (*journal_set).clone();
+                        let t: HtmlSelectElement = e.target_unchecked_into();
+                        if let Some((m, _)) = DevigMethod::all().get(t.selected_index().max(0) as usize) { v.devig = *m; }
+                        journal_set.set(v);
+                    });
+                    html!{
+                        <div class="input-group">
+                            <label>{"De-vig Method"}</label>
+                            <select onchange={on_devig} aria-label="De-vig method">
+                                { for DevigMethod::all().iter().map(|(m, name)| html!{ <option selected={*m == journal.devig}>{ *name }</option> }) }
+                            </select>
+                        </div>
+                    }
+                }}
+                <div class="metric-grid">
+                    <div class="metric-item">
+                        <div class={format!("metric-value {}", if clv_odds_avg > 0.0 { "success" } else { "danger" })}>
+                            { if clv_rows.is_empty() { "—".into() } else { format!("{:+.2}%", 100.0*clv_odds_avg) }}
+                        </div>
+                        <div class="metric-label">{"Avg CLV (Odds)"}</div>
+                    </div>
+                    <div class="metric-item">
+                        <div class={format!("metric-value {}", if clv_prob_avg > 0.0 { "success" } else { "danger" })}>
+                            { if clv_rows.is_empty() { "—".into() } else { format!("{:+.2}%", 100.0*clv_prob_avg) }}
+                        </div>
+                        <div class="metric-label">{"Avg CLV (Probability)"}</div>
+                    </div>
+                </div>
+                { for [("By Market", &clv_by_market), ("By Book", &clv_by_book)].into_iter().map(|(title, rows)| html!{
+                    <>
+                        { if rows.is_empty() { html!{} } else { html!{ <div class="muted" style="margin-top:16px;">{title}</div> } }}
+                        { for rows.iter().map(|(k, n, o, p)| html!{
+                            <div style="padding:8px; background:rgba(255,255,255,0.02); border-radius:6px; margin-top:6px;">
+                                <strong>{k}</strong>
+                                <div style="font-size:12px; color: var(--muted); margin-top:2px;">
+                                    {format!("{} bets | CLV {:+.2}% odds | {:+.2}% prob", n, 100.0*o, 100.0*p)}
+                                </div>
+                            </div>
+                        }) }
+                    </>
+                }) }
+            </div>
+
+            <footer>
+                {"EdgeRunner v0.1 - Professional Kelly Criterion calculator for optimal bet sizing"}
+            </footer>
+        </div>
+    }
+}
+
+fn parse_any(s: &str) -> Option<f64> { // decimal odds
+    let s = s.trim();
+    // try decimal ("+150" is American, not 150.0)
+    if !s.starts_with('+') { if let Ok(v) = s.parse::<f64>() { if v > 1.0 { return Some(v); } } }
+    // american
+    if let Some(d) = parse_american(s) { return Some(d); }
+    // fractional
+    if let Some(d) = parse_fractional(s) { return Some(d); }
+    None
+}
+
+fn parse_american(s: &str) -> Option<f64> { // returns decimal odds
     let s = s.trim().replace(',', "");
     let s = s.as_str();
     if s.is_empty() { return None; }
@@ -833,62 +3768,857 @@ fn parse_american(s: &str) -> Option<f64> { // returns decimal odds
     if n > 0 { Some(1.0 + (n as f64)/100.0) } else { Some(1.0 + 100.0/(-(n as f64))) }
 }
 
-fn parse_fractional(s: &str) -> Option<f64> { // returns decimal odds
-    let s = s.trim();
-    let parts: Vec<&str> = s.split('/').collect();
-    if parts.len() != 2 { return None; }
-    let num = parts[0].trim().parse::<f64>().ok()?;
-    let den = parts[1].trim().parse::<f64>().ok()?;
-    if den <= 0.0 { return None; }
-    Some(1.0 + num/den)
+fn parse_fractional(s: &str) -> Option<f64> { // returns decimal odds
+    let s = s.trim();
+    let parts: Vec<&str> = s.split('/').collect();
+    if parts.len() != 2 { return None; }
+    let num = parts[0].trim().parse::<f64>().ok()?;
+    let den = parts[1].trim().parse::<f64>().ok()?;
+    if den <= 0.0 { return None; }
+    Some(1.0 + num/den)
+}
+
+fn format_decimal(d: f64) -> String { format!("{:.3}", d) }
+// Boosted decimal odds from a boost input: "25%" boosts the profit on `d`, anything else is read as boosted odds.
+fn parse_boost(s: &str, d: f64) -> Option<f64> {
+    let s = s.trim();
+    if let Some(pct) = s.strip_suffix('%') {
+        let pct = pct.trim().trim_start_matches('+').parse::<f64>().ok()?;
+        return if pct > 0.0 { Some(1.0 + (d - 1.0) * (1.0 + pct / 100.0)) } else { None };
+    }
+    parse_any(s).filter(|&db| db > d)
+}
+
+fn cap_fraction(s: &str, bank: f64) -> f64 { // max stake as a fraction of bankroll, blank = no cap
+    match s.trim().replace(',', "").parse::<f64>() {
+        Ok(cap) if cap >= 0.0 && bank > 0.0 => cap / bank,
+        _ => f64::INFINITY,
+    }
+}
+
+// ---- Kelly with a boosted price paid on the first `cap` of stake, normal odds `d` beyond ----
+// Win with probability p, lose with q; any remaining 1 - p - q is a push (stake returned).
+// The payoff is concave in the stake, so the optimum sits in the boosted segment, at the cap,
+// or in the normal segment (closed form for each).
+fn kelly_boosted(p: f64, q: f64, d: f64, d_boost: f64, cap: f64) -> f64 {
+    let b = d - 1.0;
+    let bb = (d_boost - 1.0).max(b);
+    if b <= 0.0 || p + q <= 0.0 { return 0.0; }
+    let f_boost = ((bb * p - q) / (bb * (p + q))).clamp(0.0, 1.0);
+    if f_boost <= cap { return f_boost; }
+    let f_normal = (b * p - q - q * cap * (bb - b)) / (b * (p + q));
+    f_normal.clamp(cap, 1.0)
+}
+
+// Index of the quote (p, decimal, boosted decimal, boost cap) with the highest Kelly log growth, ties to the
+// higher boosted price, so a boosted book can beat a better base price elsewhere
+fn best_quote(quotes: &[(f64, f64, f64, f64)]) -> usize {
+    let growth = |&(p, d, d_boost, cap): &(f64, f64, f64, f64)| {
+        let f = kelly_boosted(p, 1.0 - p, d, d_boost, cap);
+        if f <= 0.0 { 0.0 } else { p * (1.0 + f * boosted_b(f, d, d_boost, cap)).ln() + (1.0 - p) * (1.0 - f).ln() }
+    };
+    let mut best = 0;
+    for (i, q) in quotes.iter().enumerate() {
+        let (g, gb) = (growth(q), growth(&quotes[best]));
+        if g > gb || (g == gb && q.2 > quotes[best].2) { best = i; }
+    }
+    best
+}
+
+// Highest base price per selection over every book in a Compare group, with its row. Kelly may size at a lower
+// boosted price, but sure bets and middles need the best price on offer.
+fn best_prices(items: &[CompareCalc], rows: &[CompareRow]) -> BTreeMap<String, (usize, f64)> {
+    let mut best: BTreeMap<String, (usize, f64)> = BTreeMap::new();
+    for c in items.iter() {
+        for (i, d) in std::iter::once((c.idx, c.d)).chain(c.others.iter().copied()) {
+            let e = best.entry(rows[c.idx].name.trim().to_string()).or_insert((i, d));
+            if d > e.1 { *e = (i, d); }
+        }
+    }
+    best
+}
+
+fn boosted_b(f: f64, d: f64, d_boost: f64, cap: f64) -> f64 { // net profit per 1 staked at stake f
+    if f <= cap || f <= 0.0 { return d_boost - 1.0; }
+    ((d_boost - 1.0) * cap + (d - 1.0) * (f - cap)) / f
+}
+
+fn complement_decimal(d: f64) -> f64 {
+    // Convert decimal odds for an event to the opposite side under no-vig assumption.
+    // d_opposite = d / (d - 1)
+    if d <= 1.0 { return f64::NAN; }
+    d / (d - 1.0)
+}
+
+fn parse_ah_line(s: &str) -> Option<f64> { // quarter-goal lines within ±3.75
+    let line = s.trim().trim_start_matches('+').parse::<f64>().ok()?;
+    if line.abs() > 3.75 || ((line * 4.0).round() - line * 4.0).abs() > 1e-9 { return None; }
+    Some(line)
+}
+
+// Gross return per 1 staked on an Asian handicap `line` at odds `d` when the backed side wins by `margin`.
+// Quarter lines are half the stake on line - 0.25 and half on line + 0.25.
+fn asian_return(margin: f64, line: f64, d: f64) -> f64 {
+    let settle = |l: f64| {
+        let m = margin + l;
+        if m.abs() < 1e-9 { 1.0 } else if m > 0.0 { d } else { 0.0 }
+    };
+    if (line * 4.0).round() as i64 % 2 != 0 {
+        (settle(line - 0.25) + settle(line + 0.25)) / 2.0
+    } else {
+        settle(line)
+    }
+}
+
+// Joint win/lose distribution of parlay legs (probability per win mask, bit j = leg j won) that keeps each leg's
+// probability and moves P(all win) from the independent value by the relative `adj` (0.1 = +10%).
+// Independence is mixed with the comonotone joint (legs win together as often as possible) for adj > 0, or with
+// the legs' win intervals laid end to end (as rarely as possible) for adj < 0; the mix weight is clamped to [0, 1].
+fn parlay_joint(p: &[f64], adj: f64) -> Vec<f64> {
+    let full = (1usize << p.len()) - 1;
+    let independent: Vec<f64> = (0..=full)
+        .map(|mask| p.iter().enumerate().map(|(j, pj)| if mask & (1 << j) != 0 { *pj } else { 1.0 - pj }).product())
+        .collect();
+    let starts: Vec<f64> = if adj > 0.0 { vec![0.0; p.len()] } else {
+        p.iter().scan(0.0, |s, pj| { let start = *s; *s += pj; Some(start) }).collect()
+    };
+    let extreme = interval_joint(p, &starts);
+    let (p_ind, p_ext) = (independent[full], extreme[full]);
+    let lambda = if (p_ext - p_ind).abs() < 1e-15 { 0.0 } else { (p_ind * adj / (p_ext - p_ind)).clamp(0.0, 1.0) };
+    independent.iter().zip(extreme.iter()).map(|(a, b)| (1.0 - lambda) * a + lambda * b).collect()
+}
+
+// Legs driven by one uniform U: leg j wins when U falls in [start_j, start_j + p_j) wrapped onto [0, 1)
+fn interval_joint(p: &[f64], starts: &[f64]) -> Vec<f64> {
+    let mut cuts = vec![0.0, 1.0];
+    for (s, pj) in starts.iter().zip(p) {
+        cuts.push(s.rem_euclid(1.0));
+        cuts.push((s + pj).rem_euclid(1.0));
+    }
+    cuts.sort_by(f64::total_cmp);
+    let mut joint = vec![0.0; 1 << p.len()];
+    for w in cuts.windows(2).filter(|w| w[1] > w[0]) {
+        let u = (w[0] + w[1]) / 2.0;
+        let mask = starts.iter().zip(p).enumerate()
+            .filter(|(_, (s, pj))| (u - **s).rem_euclid(1.0) < **pj)
+            .fold(0, |m, (j, _)| m | (1 << j));
+        joint[mask] += w[1] - w[0];
+    }
+    joint
+}
+
+// Joint Kelly over correlated Compare selections. Each group is one categorical copula leg whose selections take
+// disjoint slices of the latent normal's range, so at most one of them wins. A correlation between selections of
+// two groups becomes the correlation of the groups' latent normals: the first selection of a group named in a
+// correlation sits at the bottom of the range and the second at the top (flipping the sign), so a positive ρ still
+// means "win together". Pairs in one group, a third correlated selection in a group and a second correlation
+// between the same two groups can't be represented; they are skipped and listed in `notes`.
+fn correlated_kelly(legs: &[CorrLeg], correlations: &[CorrRow]) -> CorrCalc {
+    let mut groups: Vec<(&str, Vec<usize>)> = Vec::new();
+    for (j, l) in legs.iter().enumerate() {
+        match groups.iter_mut().find(|g| g.0 == l.group.trim()) {
+            Some(g) => g.1.push(j),
+            None => groups.push((l.group.trim(), vec![j])),
+        }
+    }
+    let group_of = |j: usize| groups.iter().position(|g| g.1.contains(&j)).unwrap_or(0);
+    let find = |name: &str, group: &str| -> Result<usize, String> {
+        let hits: Vec<usize> = legs.iter().enumerate()
+            .filter(|(_, l)| l.name.trim() == name.trim() && (group.trim().is_empty() || l.group.trim() == group.trim()))
+            .map(|x| x.0).collect();
+        match hits.as_slice() {
+            [j] => Ok(*j),
+            [] => Err(format!("{} is not a priced selection; its correlation is skipped.", name)),
+            _ => Err(format!("{} is in several groups; pick its group to use the correlation.", name)),
+        }
+    };
+    let ng = groups.len();
+    let mut notes = Vec::new();
+    let mut anchors: Vec<Vec<usize>> = vec![vec![]; ng]; // selections pinned to the bottom, then the top of their leg
+    let mut m: Vec<Vec<f64>> = (0..ng).map(|i| (0..ng).map(|k| if i == k { 1.0 } else { 0.0 }).collect()).collect();
+    let mut paired = vec![vec![false; ng]; ng];
+    for c in correlations {
+        let (a, b) = match (find(&c.a, &c.a_group), find(&c.b, &c.b_group)) {
+            (Ok(a), Ok(b)) => (a, b),
+            (Err(e), _) | (_, Err(e)) => { notes.push(e); continue; }
+        };
+        let (ga, gb) = (group_of(a), group_of(b));
+        if ga == gb {
+            notes.push(format!("{} and {} are outcomes of one group and already exclusive; correlation skipped.", c.a, c.b));
+            continue;
+        }
+        let fits = |g: usize, j: usize| anchors[g].contains(&j) || anchors[g].len() < 2;
+        if !fits(ga, a) || !fits(gb, b) {
+            notes.push(format!("At most two selections per group can carry correlations; {} / {} skipped.", c.a, c.b));
+            continue;
+        }
+        if paired[ga][gb] {
+            notes.push(format!("{} and {} already have a correlation between their groups; {} / {} skipped.", groups[ga].0, groups[gb].0, c.a, c.b));
+            continue;
+        }
+        let mut sign = 1.0;
+        for (g, j) in [(ga, a), (gb, b)] {
+            if !anchors[g].contains(&j) { anchors[g].push(j); }
+            if anchors[g][0] != j { sign = -sign; }
+        }
+        m[ga][gb] = sign * c.rho;
+        m[gb][ga] = sign * c.rho;
+        paired[ga][gb] = true;
+        paired[gb][ga] = true;
+    }
+    // Slices of probability space per group: (leg, from, to); over-round groups are scaled down to 100%
+    let slices: Vec<Vec<(usize, f64, f64)>> = groups.iter().zip(anchors.iter()).map(|((_, members), pinned)| {
+        let total: f64 = members.iter().map(|j| legs[*j].p).sum::<f64>().max(1.0);
+        let mut from = 0.0;
+        let mut out = Vec::new();
+        for j in pinned.first().into_iter().chain(members.iter().filter(|j| !pinned.contains(j))) {
+            out.push((*j, from, from + legs[*j].p / total));
+            from += legs[*j].p / total;
+        }
+        if let Some(&top) = pinned.get(1) { out.push((top, 1.0 - legs[top].p / total, 1.0)); }
+        out
+    }).collect();
+    let solve = |m: &[Vec<f64>]| {
+        let (scenarios, shrink) = copula_scenarios(&slices, m, COPULA_DRAWS, COPULA_SEED);
+        let probs: Vec<f64> = scenarios.iter().map(|s| s.1).collect();
+        let returns: Vec<Vec<f64>> = scenarios.iter()
+            .map(|(mask, _)| legs.iter().enumerate().map(|(j, l)| if mask & (1 << j) != 0 { l.d } else { 0.0 }).collect())
+            .collect();
+        let f = kelly_scenarios(&probs, &returns, 1.0);
+        let g = log_growth(&probs, &returns, &f);
+        (f, g, shrink)
+    };
+    let identity: Vec<Vec<f64>> = (0..ng).map(|i| (0..ng).map(|k| if i == k { 1.0 } else { 0.0 }).collect()).collect();
+    let ((correlated, g_corr, shrink), (independent, g_indep, _)) = (solve(&m), solve(&identity));
+    CorrCalc { correlated, independent, g_corr, g_indep, shrink, notes }
+}
+
+// ---- Gaussian copula over categorical legs ----
+// Leg i has a latent standard normal; its outcome j wins when Φ(z_i) falls in the slice [from, to) of probability
+// space, so one leg's outcomes are exclusive. Returns the distinct win masks (bit j per outcome) with their
+// frequencies, and the factor applied to the off-diagonal correlations to make them positive definite.
+fn copula_scenarios(legs: &[Vec<(usize, f64, f64)>], corr: &[Vec<f64>], draws: usize, seed: u64) -> (Vec<(u64, f64)>, f64) {
+    let n = legs.len();
+    let mut shrink = 1.0;
+    let l = loop {
+        let m: Vec<Vec<f64>> = corr.iter().enumerate()
+            .map(|(i, row)| row.iter().enumerate().map(|(j, c)| if i == j { 1.0 } else { c * shrink }).collect())
+            .collect();
+        if let Some(l) = cholesky(&m) { break l; }
+        shrink *= 0.9;
+    };
+    let cut = |u: f64| if u <= 0.0 { f64::NEG_INFINITY } else if u >= 1.0 { f64::INFINITY } else { inv_norm_cdf(u) };
+    let thresholds: Vec<Vec<(usize, f64, f64)>> = legs.iter()
+        .map(|leg| leg.iter().map(|(j, from, to)| (*j, cut(*from), cut(*to))).collect())
+        .collect();
+    let mut rng = SplitMix64(seed);
+    let mut counts: BTreeMap<u64, usize> = BTreeMap::new();
+    for _ in 0..draws {
+        let eps: Vec<f64> = (0..n).map(|_| rng.normal()).collect();
+        let mut mask = 0u64;
+        for (row, leg) in l.iter().zip(thresholds.iter()) {
+            let z: f64 = row.iter().zip(eps.iter()).map(|(a, e)| a * e).sum();
+            if let Some((j, _, _)) = leg.iter().find(|(_, lo, hi)| z >= *lo && z < *hi) { mask |= 1 << j; }
+        }
+        *counts.entry(mask).or_default() += 1;
+    }
+    let scenarios = counts.into_iter().map(|(mask, c)| (mask, c as f64 / draws as f64)).collect();
+    (scenarios, shrink)
+}
+
+fn cholesky(a: &[Vec<f64>]) -> Option<Vec<Vec<f64>>> { // lower-triangular L with L·Lᵀ = a
+    let n = a.len();
+    let mut l = vec![vec![0.0; n]; n];
+    for i in 0..n {
+        for j in 0..=i {
+            let s: f64 = (0..j).map(|k| l[i][k] * l[j][k]).sum();
+            if i == j {
+                let v = a[i][i] - s;
+                if v <= 1e-10 { return None; }
+                l[i][j] = v.sqrt();
+            } else {
+                l[i][j] = (a[i][j] - s) / l[j][j];
+            }
+        }
+    }
+    Some(l)
+}
+
+fn inv_norm_cdf(p: f64) -> f64 { // Acklam's rational approximation, |error| < 1.2e-9
+    const A: [f64; 6] = [-3.969683028665376e+01, 2.209460984245205e+02, -2.759285104469687e+02, 1.38357751867269e+02, -3.066479806614716e+01, 2.506628277459239e+00];
+    const B: [f64; 5] = [-5.447609879822406e+01, 1.615858368580409e+02, -1.556989798598866e+02, 6.680131188771972e+01, -1.328068155288572e+01];
+    const C: [f64; 6] = [-7.784894002430293e-03, -3.223964580411365e-01, -2.400758277161838e+00, -2.549732539343734e+00, 4.374664141464968e+00, 2.938163982698783e+00];
+    const D: [f64; 4] = [7.784695709041462e-03, 3.224671290700398e-01, 2.445134137142996e+00, 3.754408661907416e+00];
+    let p_low = 0.02425;
+    if p < p_low {
+        let q = (-2.0 * p.ln()).sqrt();
+        (((((C[0]*q + C[1])*q + C[2])*q + C[3])*q + C[4])*q + C[5]) / ((((D[0]*q + D[1])*q + D[2])*q + D[3])*q + 1.0)
+    } else if p <= 1.0 - p_low {
+        let q = p - 0.5; let r = q * q;
+        (((((A[0]*r + A[1])*r + A[2])*r + A[3])*r + A[4])*r + A[5])*q / (((((B[0]*r + B[1])*r + B[2])*r + B[3])*r + B[4])*r + 1.0)
+    } else {
+        -inv_norm_cdf(1.0 - p)
+    }
+}
+
+// Small deterministic PRNG so copula results don't jump between renders
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+    fn uniform(&mut self) -> f64 { // in (0, 1)
+        ((self.next_u64() >> 11) as f64 + 0.5) / (1u64 << 53) as f64
+    }
+    fn normal(&mut self) -> f64 { // Box-Muller
+        let (u1, u2) = (self.uniform(), self.uniform());
+        (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+struct CashoutEval { alpha: f64, fair_offer: f64, hold_ev: f64, g_hold: f64, g_accept: f64, g_best: f64 }
+
+// ---- Cash-out: sell a fraction alpha of a bet paying `payout` (prob p) for alpha * offer ----
+// Wealth is bank + payout + alpha (offer - payout) on a win and bank + alpha offer on a loss;
+// setting the derivative of expected log wealth to zero gives alpha in closed form.
+fn cashout_eval(bank: f64, payout: f64, offer: f64, p: f64) -> CashoutEval {
+    let q = 1.0 - p;
+    let growth = |a: f64| {
+        let (w_win, w_lose) = (bank + payout + a * (offer - payout), bank + a * offer);
+        let term = |pr: f64, w: f64| if pr <= 0.0 { 0.0 } else if w <= 0.0 { f64::NEG_INFINITY } else { pr * (w / bank).ln() };
+        term(p, w_win) + term(q, w_lose)
+    };
+    let alpha = if offer >= payout { 1.0 } else if offer <= 0.0 { 0.0 } else {
+        let k = offer - payout;
+        ((-p * k * bank - q * offer * (bank + payout)) / (k * offer)).clamp(0.0, 1.0)
+    };
+    let fair_offer = (p * (bank + payout).ln() + q * bank.ln()).exp() - bank;
+    CashoutEval { alpha, fair_offer, hold_ev: p * payout, g_hold: growth(0.0), g_accept: growth(1.0), g_best: growth(alpha) }
 }
 
-fn format_decimal(d: f64) -> String { format!("{:.3}", d) }
-fn format_american(d: f64) -> String {
-    if d <= 1.0 { return "—".into(); }
-    let b = d - 1.0;
-    if d >= 2.0 { // positive American odds
-        let n = (b * 100.0).round() as i64;
-        format!("+{}", n)
-    } else {
-        let n = (100.0 / b).round() as i64;
-        format!("-{}", n)
+// Whole-number results of a middle where bet a (decimal da) wins above lo and bet b (decimal db) wins below hi,
+// with the return per 1 staked across both at the equal-payout split: both win strictly inside the window, and a
+// bet whose line is the result itself pushes (refunds its share). Empty when the window holds no whole number.
+fn middle_hits(lo: f64, hi: f64, da: f64, db: f64) -> Vec<(f64, f64)> {
+    let book = 1.0 / da + 1.0 / db;
+    let (sa, sb) = ((1.0 / da) / book, (1.0 / db) / book);
+    let mut out = Vec::new();
+    let mut x = lo.ceil();
+    while x <= hi {
+        let ret_a = if x > lo { sa * da } else { sa };
+        let ret_b = if x < hi { sb * db } else { sb };
+        out.push((x, ret_a + ret_b));
+        x += 1.0;
     }
+    out
 }
-fn format_fractional(d: f64) -> String {
-    if d <= 1.0 { return "—".into(); }
-    let b = d - 1.0;
-    // represent b as a simple fraction with small denominator
-    let (num, den) = approx_fraction(b, 1_000, 100);
-    format!("{}/{}", num, den)
-}
-
-fn approx_fraction(x: f64, max_den: i64, max_iter: i32) -> (i64, i64) {
-    // continued fraction approximation
-    let mut x = x;
-    let mut a0 = x.floor();
-    let mut h0: i64 = 1; let mut k0: i64 = 0;
-    let mut h1: i64 = a0 as i64; let mut k1: i64 = 1;
-    let mut iter = 0;
-    while iter < max_iter {
-        let frac = x - a0;
-        if frac.abs() < 1e-9 { break; }
-        x = 1.0/frac;
-        a0 = x.floor();
-        let h2 = h0 + (a0 as i64)*h1;
-        let k2 = k0 + (a0 as i64)*k1;
-        if k2 > max_den { break; }
-        h0 = h1; k0 = k1; h1 = h2; k1 = k2;
-        iter += 1;
-    }
-    (h1, k1)
+
+// Line from a selection name: "Over 47.5" / "Under 48" (totals) or "<team> -3.5" / "<team> +4" (spreads)
+fn parse_line(name: &str) -> Option<(LineKind, String, f64)> {
+    let name = name.trim();
+    let (head, last) = name.rsplit_once(char::is_whitespace)?;
+    let line = last.parse::<f64>().ok()?;
+    let head = head.trim().to_lowercase();
+    match head.as_str() {
+        "over" | "o" => Some((LineKind::Over, String::new(), line)),
+        "under" | "u" => Some((LineKind::Under, String::new(), line)),
+        _ if last.starts_with('+') || last.starts_with('-') => Some((LineKind::Spread, head, line)),
+        _ => None,
+    }
 }
 
-fn complement_decimal(d: f64) -> f64 {
-    // Convert decimal odds for an event to the opposite side under no-vig assumption.
-    // d_opposite = d / (d - 1)
-    if d <= 1.0 { return f64::NAN; }
-    d / (d - 1.0)
+// Trigger a browser download of `content` as `filename`
+fn download_file(filename: &str, mime: &str, content: &str) {
+    let blob = gloo::file::Blob::new_with_options(content, Some(mime));
+    let url = gloo::file::ObjectUrl::from(blob);
+    if let Ok(a) = gloo::utils::document().create_element("a") {
+        let a: HtmlAnchorElement = a.unchecked_into();
+        a.set_href(&url);
+        a.set_download(filename);
+        a.click();
+    }
+    // keep the object URL alive until the browser has picked up the download
+    gloo::timers::callback::Timeout::new(1_000, move || drop(url)).forget();
+}
+
+// Read the file picked in an <input type="file"> as text; the reader lives in `slot` until it finishes
+fn read_picked_file(e: Event, slot: Rc<RefCell<Option<FileReader>>>, on_text: Callback<String>) {
+    let input: HtmlInputElement = e.target_unchecked_into();
+    if let Some(file) = input.files().and_then(|files| files.get(0)) {
+        let file = gloo::file::File::from(file);
+        *slot.borrow_mut() = Some(gloo::file::callbacks::read_as_text(&file, move |res| {
+            if let Ok(text) = res { on_text.emit(text); }
+        }));
+    }
+    input.set_value("");
+}
+
+fn format_timestamp(ms: f64) -> String { // "YYYY-MM-DD HH:MM" in UTC
+    let iso: String = js_sys::Date::new(&JsValue::from_f64(ms)).to_iso_string().into();
+    iso.chars().take(16).collect::<String>().replace('T', " ")
+}
+
+// Remove the margin from implied probabilities; a single price (nothing to normalize against) is returned as is
+fn devig(implied: &[f64], method: DevigMethod) -> Vec<f64> {
+    let total: f64 = implied.iter().sum();
+    if implied.len() < 2 || total <= 0.0 { return implied.to_vec(); }
+    match method {
+        DevigMethod::None => implied.to_vec(),
+        DevigMethod::Multiplicative => implied.iter().map(|p| p / total).collect(),
+        DevigMethod::Additive => {
+            let shift = (total - 1.0) / implied.len() as f64;
+            implied.iter().map(|p| (p - shift).clamp(0.0, 1.0)).collect()
+        }
+        DevigMethod::Power => {
+            // find k with sum p_i^k = 1 (bisection; the sum is decreasing in k)
+            let (mut lo, mut hi) = (0.01_f64, 100.0_f64);
+            for _ in 0..100 {
+                let k = 0.5 * (lo + hi);
+                if implied.iter().map(|p| p.powf(k)).sum::<f64>() > 1.0 { lo = k; } else { hi = k; }
+            }
+            let k = 0.5 * (lo + hi);
+            implied.iter().map(|p| p.powf(k)).collect()
+        }
+    }
+}
+
+// Shareable state: JSON, base64url-encoded into the URL hash after STATE_HASH_PREFIX
+fn encode_state(state: &AppState) -> String {
+    URL_SAFE_NO_PAD.encode(serde_json::to_vec(state).unwrap_or_default())
+}
+
+fn decode_state(s: &str) -> Option<AppState> {
+    serde_json::from_slice(&URL_SAFE_NO_PAD.decode(s.trim()).ok()?).ok()
+}
+
+// State from a shared link. The hash is then removed from the address bar, so a reload restores the saved
+// working state (with any edits made since) instead of the link.
+fn state_from_hash() -> Option<AppState> {
+    let window = gloo::utils::window();
+    let location = window.location();
+    let state = decode_state(location.hash().ok()?.strip_prefix(STATE_HASH_PREFIX)?)?.validated();
+    let url = format!("{}{}", location.pathname().unwrap_or_default(), location.search().unwrap_or_default());
+    if let Ok(history) = window.history() { let _ = history.replace_state_with_url(&JsValue::NULL, "", Some(&url)); }
+    Some(state)
+}
+
+fn load_state() -> Option<AppState> {
+    migrate_state(LocalStorage::get::<serde_json::Value>(STATE_KEY).ok()?).map(AppState::validated)
+}
+
+fn save_state(state: &AppState) {
+    let _ = LocalStorage::set(STATE_KEY, state_envelope(state));
+}
+
+fn state_envelope(state: &AppState) -> serde_json::Value {
+    serde_json::json!({ "version": STATE_VERSION, "state": state })
+}
+
+// Read a saved state envelope, upgrading older versions to STATE_VERSION as the schema changes. Unversioned
+// saves and versions this build doesn't know (e.g. from a newer build) are dropped rather than misread.
+fn migrate_state(mut saved: serde_json::Value) -> Option<AppState> {
+    match saved.get("version")?.as_u64()? {
+        STATE_VERSION => serde_json::from_value(saved.get_mut("state")?.take()).ok(),
+        _ => None,
+    }
+}
+
+// Link to the current page carrying `state`, for the clipboard; the address bar is left alone
+fn share_link(state: &AppState) -> Option<String> {
+    let href = gloo::utils::window().location().href().ok()?;
+    Some(format!("{}{}{}", href.split('#').next().unwrap_or(&href), STATE_HASH_PREFIX, encode_state(state)))
+}
+
+// Split CSV / TSV / semicolon text into records, each with the 1-based source line it starts on (blank lines
+// are skipped); quoted fields may contain the delimiter, "" and newlines
+fn parse_delimited(text: &str) -> Vec<(usize, Vec<String>)> {
+    let first = text.lines().find(|l| !l.trim().is_empty()).unwrap_or("");
+    let delim = ['\t', ';', ','].into_iter().max_by_key(|d| first.matches(*d).count()).filter(|d| first.contains(*d)).unwrap_or(',');
+    let (mut records, mut record, mut field) = (Vec::new(), Vec::new(), String::new());
+    let (mut quoted, mut chars) = (false, text.chars().peekable());
+    let (mut line, mut start) = (1, 1);
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => { field.push('"'); chars.next(); }
+            '"' if quoted => quoted = false,
+            '"' if field.trim().is_empty() => { field.clear(); quoted = true; }
+            c if c == delim && !quoted => record.push(std::mem::take(&mut field)),
+            '\n' | '\r' if quoted => {
+                field.push(c);
+                if c == '\r' && chars.peek() == Some(&'\n') { field.push('\n'); chars.next(); }
+                line += 1;
+            }
+            '\n' | '\r' => {
+                if c == '\r' && chars.peek() == Some(&'\n') { chars.next(); }
+                line += 1;
+                record.push(std::mem::take(&mut field));
+                if record.iter().any(|f| !f.trim().is_empty()) { records.push((start, std::mem::take(&mut record))); } else { record.clear(); }
+                start = line;
+            }
+            c => field.push(c),
+        }
+    }
+    record.push(field);
+    if record.iter().any(|f| !f.trim().is_empty()) { records.push((start, record)); }
+    records
+}
+
+// Column for each CSV_FIELDS entry, matched on the header names
+fn csv_auto_map(header: &[String]) -> Vec<Option<usize>> {
+    let mut used = vec![false; header.len()];
+    CSV_FIELDS.iter().map(|(_, aliases)| {
+        let c = header.iter().enumerate().position(|(c, h)| !used[c] && aliases.contains(&h.trim().to_lowercase().as_str()));
+        if let Some(c) = c { used[c] = true; }
+        c
+    }).collect()
+}
+
+// Compare rows from data records (header removed) and a column mapping; invalid rows are reported by source line,
+// not imported. Odds are read in `format`, or in whichever format parses when it is None.
+fn csv_compare_rows(records: &[(usize, Vec<String>)], map: &[Option<usize>], format: Option<OddsFormat>) -> (Vec<CompareRow>, Vec<String>) {
+    let cell = |r: &Vec<String>, f: usize| map[f].and_then(|c| r.get(c)).map(|s| s.trim().to_string()).unwrap_or_default();
+    let prob_of = |s: &str| s.trim_end_matches('%').trim().parse::<f64>().ok();
+    // a probability column with every value in [0, 1] holds fractions
+    let fractions = records.iter().filter_map(|r| prob_of(&cell(&r.1, CSV_PROB))).all(|p| p <= 1.0)
+        && records.iter().all(|r| !cell(&r.1, CSV_PROB).contains('%'));
+    let (mut rows, mut errors) = (Vec::new(), Vec::new());
+    for (line, r) in records.iter() {
+        let name = cell(r, CSV_NAME);
+        let mut odds = cell(r, CSV_ODDS);
+        let prob = cell(r, CSV_PROB);
+        let boost = cell(r, CSV_BOOST);
+        let boost_cap = cell(r, CSV_BOOST_CAP);
+        if name.is_empty() { errors.push(format!("Row {}: missing name", line)); continue; }
+        let Some(d) = format.map_or_else(|| parse_any(&odds), |f| f.parse(&odds)).filter(|d| *d > 1.0) else {
+            errors.push(format!("Row {} ({}): invalid odds \"{}\"", line, name, odds));
+            continue;
+        };
+        // Compare reads any format: keep American prices signed so 150 isn't taken for decimal
+        if format == Some(OddsFormat::American) && !odds.starts_with(['+', '-']) { odds = format!("+{}", odds); }
+        let your = match prob_of(&prob) {
+            Some(p) if fractions => 100.0 * p,
+            Some(p) => p,
+            None => { errors.push(format!("Row {} ({}): invalid probability \"{}\"", line, name, prob)); continue; }
+        };
+        if !(0.0..=100.0).contains(&your) { errors.push(format!("Row {} ({}): probability {} out of range", line, name, prob)); continue; }
+        if !boost.is_empty() && parse_boost(&boost, d).is_none() { errors.push(format!("Row {} ({}): invalid boost \"{}\"", line, name, boost)); continue; }
+        let group = cell(r, CSV_GROUP);
+        rows.push(CompareRow {
+            name, odds, your, boost, boost_cap,
+            group: if group.is_empty() { "Market".into() } else { group },
+            book: cell(r, CSV_BOOK),
+            id: cell(r, CSV_ID),
+            ..Default::default()
+        });
+    }
+    (rows, errors)
+}
+
+// Compare rows from an odds feed: group "<away> @ <home> — <market>", name "<outcome>[ <point>]", one row per book.
+// Your % starts at the no-vig (multiplicative) probability averaged over the books quoting the outcome.
+fn odds_api_rows(json: &str) -> Result<Vec<CompareRow>, String> {
+    let events = match serde_json::from_str::<OddsApiFeed>(json).map_err(|e| format!("Not an odds feed: {}", e))? {
+        OddsApiFeed::Events(v) => v,
+        OddsApiFeed::Event(e) => vec![e],
+    };
+    let mut rows: Vec<CompareRow> = Vec::new();
+    let mut fair: HashMap<(String, String), Vec<f64>> = HashMap::new();
+    for ev in events.iter() {
+        for book in ev.bookmakers.iter() {
+            for m in book.markets.iter() {
+                let label = match m.key.as_str() { "h2h" => "Moneyline", "spreads" => "Spread", "totals" => "Total", k => k };
+                let group = if ev.sport_title.is_empty() { format!("{} @ {} — {}", ev.away_team, ev.home_team, label) }
+                    else { format!("{}: {} @ {} — {}", ev.sport_title, ev.away_team, ev.home_team, label) };
+                let quotes: Vec<(String, f64)> = m.outcomes.iter().filter_map(|o| {
+                    let name = match (m.key.as_str(), o.point) {
+                        ("totals", Some(pt)) => format!("{} {}", o.name, pt),
+                        (_, Some(pt)) => format!("{} {:+}", o.name, pt),
+                        (_, None) => o.name.clone(),
+                    };
+                    feed_price(o.price).map(|d| (name, d))
+                }).collect();
+                let implied: Vec<f64> = quotes.iter().map(|q| 1.0 / q.1).collect();
+                let no_vig = devig(&implied, DevigMethod::Multiplicative);
+                for ((name, d), p) in quotes.into_iter().zip(no_vig) {
+                    fair.entry((group.clone(), name.clone())).or_default().push(p);
+                    let book_name = if book.title.is_empty() { book.key.clone() } else { book.title.clone() };
+                    rows.push(CompareRow { name, group: group.clone(), book: book_name, odds: format_price(d), ..Default::default() });
+                }
+            }
+        }
+    }
+    for r in rows.iter_mut() {
+        let ps = &fair[&(r.group.clone(), r.name.clone())];
+        r.your = (1000.0 * ps.iter().sum::<f64>() / ps.len() as f64).round() / 10.0;
+    }
+    Ok(rows)
+}
+
+// Feed price as decimal odds; whole numbers at or beyond ±100 are American
+fn feed_price(price: f64) -> Option<f64> {
+    if price.fract() == 0.0 && price.abs() >= 100.0 { parse_american(&format!("{}", price as i64)) }
+    else if price > 1.0 { Some(price) } else { None }
+}
+
+fn format_price(d: f64) -> String {
+    let s = format!("{:.4}", d);
+    s.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+// Imported rows update the price of matching rows (same group, name and book) and keep their Your %; the rest are appended
+fn merge_compare_rows(existing: &[CompareRow], incoming: Vec<CompareRow>) -> Vec<CompareRow> {
+    let mut v = existing.to_vec();
+    for r in incoming {
+        match v.iter_mut().find(|e| e.group == r.group && e.name == r.name && e.book == r.book) {
+            Some(e) => e.odds = r.odds,
+            None => v.push(r),
+        }
+    }
+    v
+}
+
+// Runners from a market-book snapshot (first market). Runners seen before keep their name and Your %;
+// new ones start at the probability implied by the mid price, normalized over the market.
+fn market_book_runners(json: &str, current: &ExchangeInputs) -> Result<ExchangeInputs, String> {
+    let book = match serde_json::from_str::<MarketBookFeed>(json).map_err(|e| format!("Not a market book: {}", e))? {
+        MarketBookFeed::Books(v) => v.into_iter().next().ok_or("The market book list is empty")?,
+        MarketBookFeed::Book(b) => b,
+    };
+    let mut runners: Vec<ExchangeRunner> = Vec::new();
+    for r in book.runners.into_iter().filter(|r| r.status.as_deref().is_none_or(|s| s == "ACTIVE")) {
+        let prev = current.runners.iter().find(|p| p.id == r.selection_id);
+        let mut back: Vec<(f64, f64)> = r.ex.available_to_back.iter().filter(|l| l.price > 1.0 && l.size > 0.0).map(|l| (l.price, l.size)).collect();
+        let mut lay: Vec<(f64, f64)> = r.ex.available_to_lay.iter().filter(|l| l.price > 1.0 && l.size > 0.0).map(|l| (l.price, l.size)).collect();
+        back.sort_by(|a, b| b.0.total_cmp(&a.0));
+        lay.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let name = r.runner_name.or_else(|| prev.map(|p| p.name.clone())).unwrap_or_else(|| format!("Selection {}", r.selection_id));
+        runners.push(ExchangeRunner { id: r.selection_id, name, back, lay, yours: prev.map(|p| p.yours).unwrap_or(f64::NAN) });
+    }
+    if runners.is_empty() { return Err("No active runners in the market book".into()); }
+    let mid = |r: &ExchangeRunner| match (r.back.first(), r.lay.first()) {
+        (Some(b), Some(l)) => 2.0 / (b.0 + l.0),
+        (Some(x), None) | (None, Some(x)) => 1.0 / x.0,
+        (None, None) => 0.0,
+    };
+    let total: f64 = runners.iter().map(mid).sum();
+    for r in runners.iter_mut().filter(|r| r.yours.is_nan()) {
+        r.yours = if total > 0.0 { (1000.0 * mid(r) / total).round() / 10.0 } else { 0.0 };
+    }
+    Ok(ExchangeInputs { market: book.market_id, commission: current.commission, runners })
+}
+
+// Kelly stakes for one exchange runner, walking the back and lay ladders level by level.
+// Decimal odds are net of commission and volume-weighted over the levels filled (best level when nothing is);
+// the lay is sized by its liability (the amount lost if the runner wins). `*_price` is the average matched price.
+#[derive(Clone, Default)]
+struct ExchangeCalc {
+    back_d: f64, back_f: f64, back_stake: f64, back_ev: f64, back_capped: bool, back_price: f64, back_levels: usize,
+    lay_d: f64, lay_f: f64, lay_liability: f64, lay_stake: f64, lay_ev: f64, lay_capped: bool, lay_price: f64, lay_levels: usize,
+}
+
+fn exchange_kelly(ex: &ExchangeInputs, bank: f64) -> Vec<ExchangeCalc> {
+    let n = ex.runners.len();
+    let mut calc = vec![ExchangeCalc::default(); n];
+    let total: f64 = ex.runners.iter().map(|r| r.yours.max(0.0)).sum();
+    if n == 0 || total <= 0.0 || bank <= 0.0 { return calc; }
+    let p: Vec<f64> = ex.runners.iter().map(|r| r.yours.max(0.0) / total).collect();
+    let keep = 1.0 - (ex.commission / 100.0).clamp(0.0, 1.0);
+    // One variable per ladder level: (runner, is_lay, price, gross return per 1 when the runner wins / loses,
+    // upper bound as bankroll fraction). Worse levels are only filled once the better ones are exhausted,
+    // since the solver never prefers a lower return for the same outcome.
+    let mut vars: Vec<(usize, bool, f64, f64, f64, f64)> = Vec::new();
+    for (k, r) in ex.runners.iter().enumerate() {
+        for &(price, size) in r.back.iter() {
+            vars.push((k, false, price, 1.0 + (price - 1.0) * keep, 0.0, size / bank));
+        }
+        for &(price, size) in r.lay.iter() {
+            vars.push((k, true, price, 0.0, 1.0 + keep / (price - 1.0), size * (price - 1.0) / bank)); // backer's stake matched = liability / (price - 1)
+        }
+    }
+    let r: Vec<Vec<f64>> = (0..n).map(|i| vars.iter().map(|v| if v.0 == i { v.3 } else { v.4 }).collect()).collect();
+    let upper: Vec<f64> = vars.iter().map(|v| v.5).collect();
+    let f = kelly_bounded(&p, &r, 1.0, &upper);
+    // Accumulate fills per runner and side: (fraction, fraction × net return, backer's stake, backer's stake × price,
+    // levels, all levels full)
+    let mut fills = vec![[(0.0, 0.0, 0.0, 0.0, 0usize, true); 2]; n];
+    for (v, fj) in vars.iter().zip(f.iter()) {
+        let fill = &mut fills[v.0][v.1 as usize];
+        let ret = if v.1 { v.4 } else { v.3 };
+        if *fj > 1e-9 {
+            let stake = if v.1 { fj * bank / (v.2 - 1.0) } else { fj * bank };
+            fill.0 += fj;
+            fill.1 += fj * ret;
+            fill.2 += stake;
+            fill.3 += stake * v.2;
+            fill.4 += 1;
+        }
+        fill.5 &= *fj >= v.5 - 1e-6;
+    }
+    for (k, (r, c)) in ex.runners.iter().zip(calc.iter_mut()).enumerate() {
+        let [back, lay] = fills[k];
+        if let Some(&(best, _)) = r.back.first() {
+            c.back_f = back.0;
+            c.back_stake = back.2;
+            c.back_levels = back.4;
+            c.back_capped = back.0 > 1e-6 && back.5;
+            c.back_d = if back.0 > 1e-9 { back.1 / back.0 } else { 1.0 + (best - 1.0) * keep };
+            c.back_price = if back.2 > 0.0 { back.3 / back.2 } else { best };
+            c.back_ev = p[k] * c.back_d - 1.0;
+        }
+        if let Some(&(best, _)) = r.lay.first() {
+            c.lay_f = lay.0;
+            c.lay_liability = lay.0 * bank;
+            c.lay_stake = lay.2;
+            c.lay_levels = lay.4;
+            c.lay_capped = lay.0 > 1e-6 && lay.5;
+            c.lay_d = if lay.0 > 1e-9 { lay.1 / lay.0 } else { 1.0 + keep / (best - 1.0) };
+            c.lay_price = if lay.2 > 0.0 { 1.0 + c.lay_liability / lay.2 } else { best };
+            c.lay_ev = (1.0 - p[k]) * c.lay_d - 1.0;
+        }
+    }
+    calc
+}
+
+// A runner's sized back and lay as journal records (None when not staked): market id as the market and
+// "Exchange" as the book, the way the export lists them
+fn exchange_bets(ex: &ExchangeInputs, r: &ExchangeRunner, c: &ExchangeCalc) -> [Option<BetRecord>; 2] {
+    let back = (c.back_stake > 0.0).then(|| {
+        BetRecord::new(format!("Back {}", r.name), ex.market.clone(), "Exchange".into(), c.back_price, r.yours, c.back_stake, c.back_f)
+            .with_commission(ex.commission).with_market(100.0 / c.back_price)
+    });
+    let lay = (c.lay_stake > 0.0).then(|| {
+        BetRecord::new(format!("Lay {}", r.name), ex.market.clone(), "Exchange".into(), c.lay_price, 100.0 - r.yours, c.lay_stake, c.lay_f)
+            .laid().with_commission(ex.commission).with_market(100.0 - 100.0 / c.lay_price)
+    });
+    [back, lay]
+}
+
+fn exchange_recommendation(b: &BetRecord, ev: f64) -> Recommendation {
+    let rec = Recommendation::new("Exchange", b.selection.clone(), b.market.clone(), b.book.clone(), b.odds, b.prob, b.kelly, b.stake, ev);
+    if b.lay { rec.with_liability(b.risk()) } else { rec }
+}
+
+// Open a live odds socket; status and price updates go to the LiveFeed reducer
+fn live_connect(url: &str, live: UseReducerDispatcher<LiveFeed>) -> Result<LiveSocket, String> {
+    let ws = WebSocket::new(url).map_err(|e| e.as_string().unwrap_or_else(|| format!("Cannot connect to {}", url)))?;
+    let d = live.clone();
+    let on_open = Closure::<dyn FnMut(JsValue)>::new(move |_| d.dispatch(LiveAction::Status(LiveStatus::Live)));
+    let d = live.clone();
+    let mut expiries: HashMap<String, gloo::timers::callback::Timeout> = HashMap::new(); // one per id; replacing one cancels it
+    let on_message = Closure::<dyn FnMut(MessageEvent)>::new(move |e: MessageEvent| {
+        let Some(text) = e.data().as_string() else { return };
+        let Ok(msg) = serde_json::from_str::<LiveMessage>(&text) else { return };
+        let updates: Vec<PriceUpdate> = msg.updates().into_iter().filter(|u| u.odds > 1.0 && !u.id.is_empty()).collect();
+        if updates.is_empty() { return; }
+        for u in updates.iter() {
+            let (d, id, odds) = (d.clone(), u.id.clone(), u.odds);
+            expiries.insert(u.id.clone(), gloo::timers::callback::Timeout::new(LIVE_HIGHLIGHT_MS, move || d.dispatch(LiveAction::Expire(id, odds))));
+        }
+        d.dispatch(LiveAction::Updates(updates));
+    });
+    // The browser reports no detail on a socket error, so name the URL
+    let (d, target) = (live.clone(), url.to_string());
+    let on_error = Closure::<dyn FnMut(JsValue)>::new(move |_| d.dispatch(LiveAction::Status(LiveStatus::Closed(format!("Connection error ({})", target)))));
+    let d = live;
+    let on_close = Closure::<dyn FnMut(CloseEvent)>::new(move |e: CloseEvent| {
+        let reason = if e.reason().is_empty() { format!("Connection closed (code {})", e.code()) } else { format!("Connection closed: {}", e.reason()) };
+        d.dispatch(LiveAction::Status(LiveStatus::Closed(reason)));
+    });
+    ws.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+    ws.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+    ws.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+    ws.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+    Ok(LiveSocket { ws, _on_open: on_open, _on_message: on_message, _on_error: on_error, _on_close: on_close })
+}
+
+fn live_subscribe(ws: &WebSocket, ids: &[String]) {
+    if let Ok(json) = serde_json::to_string(&LiveMessage::Subscribe { ids: ids.to_vec() }) {
+        let _ = ws.send_with_str(&json);
+    }
+}
+
+// Home / draw / away probabilities from Elo ratings. Davidson: with s = 10^(diff/800),
+// P(home) ∝ s, P(away) ∝ 1/s, P(draw) ∝ ν, where ν = 2d/(1-d) makes the draw share d at equal ratings.
+fn elo_probs(e: &EloInputs) -> [f64; 3] {
+    let diff = e.home + e.home_adv - e.away;
+    let s = 10f64.powf(diff / 800.0);
+    let d = if e.draws { (e.draw_pct / 100.0).clamp(0.0, 0.95) } else { 0.0 };
+    let nu = 2.0 * d / (1.0 - d);
+    let total = s + 1.0 / s + nu;
+    [s / total, nu / total, 1.0 / s / total]
+}
+
+// Score probabilities [home goals][away goals], renormalized. Each side runs from 0 to at least
+// POISSON_MIN_GOALS and on until its remaining tail is below POISSON_TAIL, so high xG keeps its mass.
+// Dixon–Coles multiplies the four low scores by τ (floored at 0 so extreme ρ stays a distribution).
+fn score_matrix(m: &PoissonInputs) -> Vec<Vec<f64>> {
+    let pmf = |lambda: f64| {
+        let mut v = vec![(-lambda).exp()];
+        let mut cdf = v[0];
+        while v.len() <= POISSON_MIN_GOALS || (1.0 - cdf > POISSON_TAIL && v.len() <= POISSON_MAX_GOALS) {
+            let p = v[v.len() - 1] * lambda / v.len() as f64;
+            cdf += p;
+            v.push(p);
+        }
+        v
+    };
+    let (l, mu) = (m.home_xg.max(0.0), m.away_xg.max(0.0));
+    let (ph, pa) = (pmf(l), pmf(mu));
+    let mut scores: Vec<Vec<f64>> = ph.iter().map(|h| pa.iter().map(|a| h * a).collect()).collect();
+    if m.dixon_coles {
+        let rho = m.rho;
+        scores[0][0] *= (1.0 - l * mu * rho).max(0.0);
+        scores[0][1] *= (1.0 + l * rho).max(0.0);
+        scores[1][0] *= (1.0 + mu * rho).max(0.0);
+        scores[1][1] *= (1.0 - rho).max(0.0);
+    }
+    let total: f64 = scores.iter().flatten().sum();
+    if total > 0.0 { for p in scores.iter_mut().flatten() { *p /= total; } }
+    scores
+}
+
+// Totals lines in half-goal steps. A quarter line (2.25) is half the stake on each neighbour (2 and 2.5),
+// which win/push/lose can't express, so it snaps to the nearest half line and the halves are bet separately.
+fn totals_line(x: f64) -> f64 { (x * 2.0).round() / 2.0 }
+
+fn goal_markets(scores: &[Vec<f64>], line: f64) -> GoalMarkets {
+    let mut g = GoalMarkets { home: 0.0, draw: 0.0, away: 0.0, over: 0.0, push: 0.0, under: 0.0, btts: 0.0 };
+    for (h, row) in scores.iter().enumerate() {
+        for (a, p) in row.iter().enumerate() {
+            match h.cmp(&a) {
+                std::cmp::Ordering::Greater => g.home += p,
+                std::cmp::Ordering::Equal => g.draw += p,
+                std::cmp::Ordering::Less => g.away += p,
+            }
+            let total = (h + a) as f64;
+            if (total - line).abs() < 1e-9 { g.push += p; } else if total > line { g.over += p; } else { g.under += p; }
+            if h > 0 && a > 0 { g.btts += p; }
+        }
+    }
+    g
+}
+
+// Probability of each Asian-handicap goal-difference bucket (AH_MIN_GD..=-AH_MIN_GD, tails in the end buckets)
+fn goal_difference_dist(scores: &[Vec<f64>]) -> Vec<f64> {
+    let n = (1 - 2 * AH_MIN_GD) as usize;
+    let mut dist = vec![0.0; n];
+    for (h, row) in scores.iter().enumerate() {
+        for (a, p) in row.iter().enumerate() {
+            let gd = h as i32 - a as i32;
+            dist[(gd - AH_MIN_GD).clamp(0, n as i32 - 1) as usize] += p;
+        }
+    }
+    dist
+}
+
+fn place_decimal(d: f64, terms: u32) -> f64 { // each-way place odds at 1/terms of the win odds
+    1.0 + (d - 1.0) / (terms.max(1) as f64)
 }
 
 // ---- Exact Kelly for mutually exclusive outcomes (N-outcome market) ----
@@ -906,39 +4636,93 @@ fn kelly_multi_exact(p: &[f64], d: &[f64], cap: f64) -> Vec<f64> {
     }
     if sumk > cap && sumk > 0.0 {
         let scale = cap / sumk;
-        for i in 0..n { f[i] *= scale; }
+        for fi in f.iter_mut() { *fi *= scale; }
     }
 
+    // Bet i pays d_i in outcome i only
+    let r: Vec<Vec<f64>> = (0..n).map(|i| (0..n).map(|k| if k == i { d[i] } else { 0.0 }).collect()).collect();
+    kelly_ascent(p, &vec![1.0; n], &r, cap, &vec![f64::INFINITY; n], f)
+}
+
+// Expected log growth of stakes `f` over a payoff matrix (same layout as kelly_scenarios)
+fn log_growth(p: &[f64], r: &[Vec<f64>], f: &[f64]) -> f64 {
+    let fsum: f64 = f.iter().sum();
+    p.iter().zip(r.iter()).map(|(pi, ri)| {
+        let w = 1.0 - fsum + f.iter().zip(ri.iter()).map(|(fj, rij)| fj * rij).sum::<f64>();
+        if *pi <= 0.0 { 0.0 } else if w <= 0.0 { f64::NEG_INFINITY } else { pi * w.ln() }
+    }).sum()
+}
+
+// ---- Exact Kelly over a payoff matrix ----
+// Outcome i has probability p[i]; bet j returns r[i][j] per 1 staked in outcome i (stake included).
+fn kelly_scenarios(p: &[f64], r: &[Vec<f64>], cap: f64) -> Vec<f64> {
+    let m = r.first().map(|row| row.len()).unwrap_or(0);
+    if p.is_empty() || r.len() != p.len() || r.iter().any(|row| row.len() != m) { return vec![]; }
+    kelly_ascent(p, &vec![1.0; p.len()], r, cap, &vec![f64::INFINITY; m], vec![0.0; m])
+}
+
+// Same as kelly_scenarios with a ceiling on each stake (e.g. the liquidity available at that price)
+fn kelly_bounded(p: &[f64], r: &[Vec<f64>], cap: f64, upper: &[f64]) -> Vec<f64> {
+    let m = r.first().map(|row| row.len()).unwrap_or(0);
+    if p.is_empty() || r.len() != p.len() || upper.len() != m || r.iter().any(|row| row.len() != m) { return vec![]; }
+    kelly_ascent(p, &vec![1.0; p.len()], r, cap, upper, vec![0.0; m])
+}
+
+// Same as kelly_scenarios with existing positions: wealth in outcome i is base[i] before any new stake
+// (base = 1 + payout of open bets in outcome i, all as bankroll fractions).
+fn kelly_portfolio(p: &[f64], base: &[f64], r: &[Vec<f64>], cap: f64) -> Vec<f64> {
+    let m = r.first().map(|row| row.len()).unwrap_or(0);
+    if p.is_empty() || r.len() != p.len() || base.len() != p.len() || r.iter().any(|row| row.len() != m) { return vec![]; }
+    kelly_ascent(p, base, r, cap, &vec![f64::INFINITY; m], vec![0.0; m])
+}
+
+// Projected gradient ascent on sum_i p_i ln(base_i - sum_j f_j + sum_j f_j r_ij) with 0 <= f_j <= upper_j, sum f <= cap
+fn kelly_ascent(p: &[f64], base: &[f64], r: &[Vec<f64>], cap: f64, upper: &[f64], mut f: Vec<f64>) -> Vec<f64> {
+    let m = f.len();
+    let wealth = |f: &Vec<f64>, bi: f64, ri: &[f64]| -> f64 {
+        let fsum: f64 = f.iter().sum();
+        bi - fsum + f.iter().zip(ri.iter()).map(|(fj, rij)| fj * rij).sum::<f64>()
+    };
+
     // Helper closures
     let obj = |f: &Vec<f64>| -> f64 {
-        let fsum: f64 = f.iter().sum();
         let mut val = 0.0;
-        for i in 0..n {
-            let wi = 1.0 - fsum + d[i]*f[i];
+        for ((pi, bi), ri) in p.iter().zip(base.iter()).zip(r.iter()) {
+            let wi = wealth(f, *bi, ri);
             if wi <= 1e-12 { return f64::NEG_INFINITY; }
-            val += p[i] * wi.ln();
+            val += pi * wi.ln();
         }
         val
     };
     let grad = |f: &Vec<f64>| -> Vec<f64> {
-        let fsum: f64 = f.iter().sum();
-        let mut g = vec![0.0; n];
-        // precompute S = sum_i p_i / W_i
-        let mut s_over = 0.0;
-        let mut inv_w: Vec<f64> = vec![0.0; n];
-        for i in 0..n {
-            let wi = 1.0 - fsum + d[i]*f[i];
+        let mut g = vec![0.0; m];
+        for ((pi, bi), ri) in p.iter().zip(base.iter()).zip(r.iter()) {
+            let wi = wealth(f, *bi, ri);
             let inv = if wi <= 1e-12 { 1e12 } else { 1.0/wi };
-            inv_w[i] = inv;
-            s_over += p[i] * inv;
-        }
-        for k in 0..n {
-            g[k] = -s_over + p[k] * d[k] * inv_w[k];
+            for (gj, rij) in g.iter_mut().zip(ri.iter()) {
+                *gj += pi * (rij - 1.0) * inv;
+            }
         }
         g
     };
 
+    let bounded = upper.iter().any(|u| u.is_finite());
     let proj = |v: &mut Vec<f64>| {
+        if bounded {
+            // Project onto {0 <= x <= upper, sum x <= cap}: clamp after shifting down by theta, found by bisection
+            let clip = |theta: f64, x: f64, u: f64| (x - theta).clamp(0.0, u);
+            let total = |theta: f64| v.iter().zip(upper).map(|(x, u)| clip(theta, *x, *u)).sum::<f64>();
+            let theta = if total(0.0) <= cap { 0.0 } else {
+                let (mut lo, mut hi) = (0.0, v.iter().cloned().fold(0.0, f64::max));
+                for _ in 0..100 {
+                    let mid = 0.5 * (lo + hi);
+                    if total(mid) > cap { lo = mid; } else { hi = mid; }
+                }
+                hi
+            };
+            for (x, u) in v.iter_mut().zip(upper) { *x = clip(theta, *x, *u); }
+            return;
+        }
         // Project onto simplex {x >= 0, sum x <= cap}
         for x in v.iter_mut() { if *x < 0.0 { *x = 0.0; } }
         let sum: f64 = v.iter().sum();
@@ -986,3 +4770,699 @@ fn kelly_multi_exact(p: &[f64], d: &[f64], cap: f64) -> Vec<f64> {
 fn main() {
     yew::Renderer::<App>::new().render();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn boost_inputs_parse_as_profit_boosts_or_boosted_odds() {
+        assert_eq!(parse_boost("25%", 3.0), Some(3.5));
+        assert_eq!(parse_boost("+50 %", 2.0), Some(2.5));
+        assert_eq!(parse_boost("2.5", 2.0), Some(2.5));
+        assert_eq!(parse_boost("1.9", 2.0), None); // not a boost
+        assert_eq!(parse_boost("0%", 2.0), None);
+        assert_eq!(cap_fraction("", 1000.0), f64::INFINITY);
+        assert_eq!(cap_fraction("1,000", 1000.0), 1.0);
+        assert_eq!(cap_fraction("100", 0.0), f64::INFINITY);
+    }
+
+    #[test]
+    fn boosted_kelly_matches_each_closed_form_segment() {
+        // no cap: plain Kelly at the boosted price
+        assert!((kelly_boosted(0.5, 0.5, 2.0, 2.5, f64::INFINITY) - 0.5 / 3.0).abs() < 1e-12);
+        // normal price has no edge: stake stops at the cap
+        assert!((kelly_boosted(0.5, 0.5, 2.0, 2.5, 0.05) - 0.05).abs() < 1e-12);
+        // edge at both prices: optimum beyond the cap, where d/df [p ln W(win) + q ln W(lose)] = 0
+        let (p, q, d, db, cap) = (0.5, 0.5, 2.2, 3.0, 0.05);
+        let f = kelly_boosted(p, q, d, db, cap);
+        assert!(f > cap);
+        let w_win = 1.0 + (db - 1.0) * cap + (d - 1.0) * (f - cap);
+        assert!((p * (d - 1.0) / w_win - q / (1.0 - f)).abs() < 1e-12);
+        assert!((boosted_b(f, d, db, cap) * f - (w_win - 1.0)).abs() < 1e-12);
+        assert_eq!(boosted_b(0.02, d, db, cap), 2.0);
+    }
+
+    #[test]
+    fn each_way_stake_solves_the_win_place_lose_first_order_condition() {
+        assert_eq!(place_decimal(9.0, 4), 3.0);
+        assert_eq!(place_decimal(6.0, 5), 2.0);
+        // 8.0 at 1/4 odds: win pays both halves, place-only pays the place half
+        let (dw, dp) = (8.0, place_decimal(8.0, 4));
+        let (p, r) = ([0.15, 0.25, 0.60], [(dw + dp) / 2.0, dp / 2.0, 0.0]);
+        let f = kelly_scenarios(&p, &r.iter().map(|x| vec![*x]).collect::<Vec<_>>(), 1.0)[0];
+        assert!(f > 0.0);
+        let slope: f64 = p.iter().zip(r).map(|(pi, ri)| pi * (ri - 1.0) / (1.0 + f * (ri - 1.0))).sum();
+        assert!(slope.abs() < 1e-4, "{}", slope); // projected ascent tolerance
+        // closed form: with net returns a, b on win / place-only, p_w a/(1+fa) + p_p b/(1+fb) = p_l/(1-f)
+        // clears to ab f² - (p_w a(b-1) + p_p b(a-1) - p_l(a+b)) f - (p_w a + p_p b - p_l) = 0
+        let (a, b) = (r[0] - 1.0, r[1] - 1.0);
+        let lin = p[0] * a * (b - 1.0) + p[1] * b * (a - 1.0) - p[2] * (a + b);
+        let c = p[0] * a + p[1] * b - p[2];
+        let root = (lin + (lin * lin + 4.0 * a * b * c).sqrt()) / (2.0 * a * b);
+        assert!(root > 0.0 && root < 1.0);
+        assert!((f - root).abs() < 1e-5, "{} vs {}", f, root); // ascent step tolerance
+    }
+
+    #[test]
+    fn asian_quarter_lines_split_the_stake_across_the_neighbouring_lines() {
+        let d = 1.9;
+        assert_eq!(asian_return(0.0, 0.0, d), 1.0); // level ball, draw: push
+        assert_eq!(asian_return(1.0, -1.0, d), 1.0); // -1 winning by one: push
+        assert_eq!(asian_return(0.0, -0.25, d), 0.5); // half push, half loss
+        assert_eq!(asian_return(0.0, 0.25, d), (1.0 + d) / 2.0); // half push, half win
+        assert_eq!(asian_return(1.0, -0.75, d), (d + 1.0) / 2.0); // -0.5 wins, -1 pushes
+        assert_eq!(asian_return(2.0, -1.5, d), d);
+        assert_eq!(asian_return(-1.0, 0.5, d), 0.0);
+        assert_eq!(parse_ah_line("+0.75"), Some(0.75));
+        assert_eq!(parse_ah_line("-0.3"), None);
+        assert_eq!(parse_ah_line("-4"), None);
+    }
+
+    #[test]
+    fn push_probability_uses_the_three_outcome_kelly_formula() {
+        // win 50%, push 10%, lose 40% at evens: f = (b p - q) / (b (p + q))
+        let f = kelly_boosted(0.5, 0.4, 2.0, 2.0, f64::INFINITY);
+        assert!((f - 0.1 / 0.9).abs() < 1e-12);
+        assert!((0.5 / (1.0 + f) - 0.4 / (1.0 - f)).abs() < 1e-12);
+        // counting the push as a loss shrinks the stake to the two-outcome Kelly
+        assert!(kelly_boosted(0.5, 0.5, 2.0, 2.0, f64::INFINITY).abs() < 1e-12);
+        assert_eq!(kelly_boosted(0.0, 0.0, 2.0, 2.0, f64::INFINITY), 0.0);
+    }
+
+    #[test]
+    fn parlay_joint_keeps_leg_probabilities_and_moves_the_all_win_mass() {
+        let p = [0.6, 0.5, 0.7];
+        let marginal = |joint: &[f64], j: usize| joint.iter().enumerate().filter(|(m, _)| m & (1 << j) != 0).map(|x| x.1).sum::<f64>();
+        for adj in [-0.5, 0.0, 0.2, 10.0] {
+            let joint = parlay_joint(&p, adj);
+            assert!((joint.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+            assert!(joint.iter().all(|x| *x >= -1e-15));
+            for (j, pj) in p.iter().enumerate() { assert!((marginal(&joint, j) - pj).abs() < 1e-12, "{} {}", adj, j); }
+        }
+        assert!((parlay_joint(&p, 0.2)[7] - 0.21 * 1.2).abs() < 1e-12);
+        assert!((parlay_joint(&p, 10.0)[7] - 0.5).abs() < 1e-12); // capped at the least likely leg
+        // two evens legs that never win together
+        assert!(parlay_joint(&[0.5, 0.5], -1.0)[3].abs() < 1e-12);
+    }
+
+    #[test]
+    fn inv_norm_cdf_matches_standard_quantiles() {
+        assert!(inv_norm_cdf(0.5).abs() < 1e-9);
+        assert!((inv_norm_cdf(0.975) - 1.959963985).abs() < 1e-8);
+        assert!((inv_norm_cdf(0.01) + 2.326347874).abs() < 1e-8); // lower tail branch
+        assert!((inv_norm_cdf(0.999) + inv_norm_cdf(0.001)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn cholesky_factors_positive_definite_matrices_only() {
+        let a = vec![vec![4.0, 2.0, 0.4], vec![2.0, 2.0, 0.5], vec![0.4, 0.5, 1.0]];
+        let l = cholesky(&a).unwrap();
+        for i in 0..3 {
+            for j in 0..3 {
+                let v: f64 = (0..3).map(|k| l[i][k] * l[j][k]).sum();
+                assert!((v - a[i][j]).abs() < 1e-12);
+            }
+            assert!(l[i][i + 1..].iter().all(|x| *x == 0.0));
+        }
+        assert!(cholesky(&[vec![1.0, 1.0], vec![1.0, 1.0]]).is_none());
+        assert!(cholesky(&[vec![1.0, 0.9, -0.9], vec![0.9, 1.0, 0.9], vec![-0.9, 0.9, 1.0]]).is_none());
+    }
+
+    #[test]
+    fn copula_keeps_group_outcomes_exclusive_and_marginals_intact() {
+        let legs = vec![vec![(0, 0.0, 0.3), (1, 0.3, 0.8)], vec![(2, 0.0, 0.5)]];
+        let corr = vec![vec![1.0, 0.6], vec![0.6, 1.0]];
+        let (s, shrink) = copula_scenarios(&legs, &corr, 20_000, COPULA_SEED);
+        assert_eq!(shrink, 1.0);
+        assert!((s.iter().map(|x| x.1).sum::<f64>() - 1.0).abs() < 1e-12);
+        assert!(s.iter().all(|(m, _)| m & 0b11 != 0b11));
+        let freq = |bit: u64| s.iter().filter(|(m, _)| m & bit != 0).map(|x| x.1).sum::<f64>();
+        for (bit, p) in [(1, 0.3), (2, 0.5), (4, 0.5)] { assert!((freq(bit) - p).abs() < 0.015, "{} {}", bit, freq(bit)); }
+        // positive correlation pulls the bottom slices together: P(0 and 2) above the independent 0.15
+        assert!(s.iter().filter(|(m, _)| m & 0b101 == 0b101).map(|x| x.1).sum::<f64>() > 0.2);
+        // perfect correlation is not positive definite and gets shrunk
+        let (_, shrink) = copula_scenarios(&legs, &[vec![1.0, 1.0], vec![1.0, 1.0]], 100, COPULA_SEED);
+        assert!(shrink < 1.0);
+    }
+
+    #[test]
+    fn correlated_kelly_skips_correlations_it_cannot_represent() {
+        let leg = |group: &str, name: &str, d: f64, p: f64| CorrLeg { group: group.into(), name: name.into(), d, p };
+        let corr = |a: &str, a_group: &str, b: &str, b_group: &str| CorrRow { a: a.into(), a_group: a_group.into(), b: b.into(), b_group: b_group.into(), rho: 0.4 };
+        let legs = vec![leg("Game 1", "Home", 2.2, 0.5), leg("Game 1", "Away", 2.4, 0.45), leg("Game 1 total", "Over", 2.0, 0.55), leg("Game 2", "Home", 2.1, 0.5)];
+        let calc = correlated_kelly(&legs, &[
+            corr("Home", "Game 1", "Over", ""),
+            corr("Home", "Game 1", "Away", "Game 1"), // same group
+            corr("Home", "", "Over", ""),             // ambiguous name
+            corr("Away", "Game 1", "Over", ""),       // second correlation between the same groups
+            corr("Draw", "", "Over", ""),             // not priced
+        ]);
+        assert_eq!(calc.notes.len(), 4);
+        assert_eq!(calc.correlated.len(), 4);
+        assert!(calc.g_corr > 0.0 && calc.g_indep > 0.0);
+        // Home and Away of one game never both win, so the stakes on them fit one bankroll
+        assert!(calc.correlated[0] + calc.correlated[1] + calc.correlated[2] + calc.correlated[3] <= 1.0 + 1e-9);
+        assert_eq!(calc.shrink, 1.0);
+    }
+
+    #[test]
+    fn portfolio_kelly_matches_the_closed_form_against_an_open_position() {
+        // one bet on outcome k at decimal d with wealth base = (b_k, b_other) before the stake:
+        // f = (p_k (d - 1) b_other - p_other b_k) / (d - 1), floored at zero
+        let closed = |pk: f64, d: f64, bk: f64, bo: f64| ((pk * (d - 1.0) * bo - (1.0 - pk) * bk) / (d - 1.0)).max(0.0);
+        // hedge: the open bet pays 1.5 if outcome 0 wins, the stake of 0.2 is lost otherwise
+        let f = kelly_portfolio(&[0.4, 0.6], &[1.5, 0.8], &[vec![0.0], vec![2.0]], 1.0);
+        assert!((f[0] - closed(0.6, 2.0, 0.8, 1.5)).abs() < 1e-4, "{:?}", f);
+        // adding to the same side is already over-exposed: no new stake
+        let f = kelly_portfolio(&[0.4, 0.6], &[1.5, 0.8], &[vec![2.0], vec![0.0]], 1.0);
+        assert!(f[0].abs() < 1e-9);
+        // without a position it is plain Kelly
+        let f = kelly_portfolio(&[0.55, 0.45], &[1.0, 1.0], &[vec![2.0], vec![0.0]], 1.0);
+        assert!((f[0] - 0.1).abs() < 1e-4);
+        assert!(kelly_portfolio(&[0.5], &[1.0, 1.0], &[vec![2.0]], 1.0).is_empty());
+    }
+
+    #[test]
+    fn cashout_alpha_is_the_log_growth_optimum_and_fair_offer_breaks_even() {
+        let (bank, payout, p) = (1_000.0, 400.0, 0.5);
+        let e = cashout_eval(bank, payout, 195.0, p);
+        assert!(e.alpha > 0.0 && e.alpha < 1.0);
+        // first-order condition: p (offer - payout) / W_win + q offer / W_lose = 0
+        let (w_win, w_lose) = (bank + payout + e.alpha * (195.0 - payout), bank + e.alpha * 195.0);
+        assert!((p * (195.0 - payout) / w_win + (1.0 - p) * 195.0 / w_lose).abs() < 1e-12);
+        assert!(e.g_best >= e.g_hold && e.g_best >= e.g_accept);
+        assert_eq!(e.hold_ev, 200.0);
+        // taking the fair offer in full grows the bankroll exactly as much as holding
+        let fair = cashout_eval(bank, payout, e.fair_offer, p);
+        assert!((fair.g_accept - fair.g_hold).abs() < 1e-12);
+        assert!(e.fair_offer < e.hold_ev);
+        // an offer at or above the payout is always taken in full, a worthless one never
+        assert_eq!(cashout_eval(bank, payout, payout, p).alpha, 1.0);
+        assert_eq!(cashout_eval(bank, payout, 0.0, p).alpha, 0.0);
+    }
+
+    #[test]
+    fn middles_need_a_whole_number_and_push_on_integer_lines() {
+        // Over 47.5 / Under 49.5 at evens: 48 and 49 win both bets
+        assert_eq!(middle_hits(47.5, 49.5, 2.0, 2.0), vec![(48.0, 2.0), (49.0, 2.0)]);
+        // Over 47.5 / Under 48: 48 wins the over and pushes the under
+        assert_eq!(middle_hits(47.5, 48.0, 2.0, 2.0), vec![(48.0, 1.5)]);
+        // Over 47 / Under 49: pushes at both ends, both win on 48
+        assert_eq!(middle_hits(47.0, 49.0, 2.0, 2.0), vec![(47.0, 1.5), (48.0, 2.0), (49.0, 1.5)]);
+        // no whole number between 47.5 and 47.9
+        assert!(middle_hits(47.5, 47.9, 2.0, 2.0).is_empty());
+        // uneven prices: the equal-payout split returns 1/book when one side wins
+        let (da, db) = (1.8, 2.5);
+        let book = 1.0 / da + 1.0 / db;
+        let hits = middle_hits(2.5, 3.5, da, db);
+        assert_eq!(hits.len(), 1);
+        assert!((hits[0].1 - 2.0 / book).abs() < 1e-12);
+    }
+
+    #[test]
+    fn parse_line_reads_totals_and_spreads() {
+        assert!(matches!(parse_line("Over 47.5"), Some((LineKind::Over, _, l)) if l == 47.5));
+        assert!(matches!(parse_line("u 48"), Some((LineKind::Under, _, l)) if l == 48.0));
+        assert!(matches!(parse_line("Chiefs -3.5"), Some((LineKind::Spread, t, l)) if t == "chiefs" && l == -3.5));
+        assert!(parse_line("Chiefs 3.5").is_none());
+        assert!(parse_line("Chiefs").is_none());
+    }
+
+    #[test]
+    fn sure_bets_use_the_best_base_price_not_the_kelly_book() {
+        let row = |name: &str| CompareRow { name: name.into(), group: "G".into(), ..Default::default() };
+        let rows = vec![row("Over 2.5"), row("Over 2.5"), row("Under 2.5")];
+        // Kelly sizes Over at row 0 (1.90 boosted), though row 1 quotes 2.10
+        let calc = |idx: usize, d: f64, others: Vec<(usize, f64)>| CompareCalc { idx, d, d_boost: 2.4, cap: f64::INFINITY, f: 0.1, ev: 0.0, avg: d, others };
+        let items = vec![calc(0, 1.9, vec![(1, 2.1)]), calc(2, 2.05, vec![])];
+        let best = best_prices(&items, &rows);
+        assert_eq!(best["Over 2.5"], (1, 2.1));
+        assert_eq!(best["Under 2.5"], (2, 2.05));
+        // 1/2.10 + 1/2.05 < 1 is a sure bet; at the Kelly book's 1.90 it would not be
+        assert!(best.values().map(|(_, d)| 1.0 / d).sum::<f64>() < 1.0);
+        assert!(items.iter().map(|c| 1.0 / c.d).sum::<f64>() > 1.0);
+    }
+
+    #[test]
+    fn best_quote_applies_boosts_before_choosing() {
+        let inf = f64::INFINITY;
+        // 2.10 plain vs 2.00 boosted to 2.30: the boost wins
+        assert_eq!(best_quote(&[(0.5, 2.1, 2.1, inf), (0.5, 2.0, 2.3, inf)]), 1);
+        // the same boost capped at 0.1% of the bankroll is worth less than the better base price
+        assert_eq!(best_quote(&[(0.5, 2.1, 2.1, inf), (0.5, 2.0, 2.3, 0.001)]), 0);
+        // without an edge anywhere, the highest boosted price is shown
+        assert_eq!(best_quote(&[(0.3, 2.0, 2.0, inf), (0.3, 1.9, 2.2, inf), (0.3, 2.1, 2.1, inf)]), 1);
+    }
+
+    #[test]
+    fn journal_stats_totals_history_and_odds_bands() {
+        let bet = |odds: f64, stake: f64, status: BetStatus, at: f64| {
+            let mut b = BetRecord::new("x".into(), "m".into(), String::new(), odds, 50.0, stake, 0.0);
+            b.settle(status, at);
+            b
+        };
+        let bets = vec![
+            bet(2.5, 10.0, BetStatus::Lost, 2.0),
+            bet(1.8, 20.0, BetStatus::Won, 1.0),
+            bet(2.2, 30.0, BetStatus::Push, 3.0),
+            bet(4.0, 40.0, BetStatus::Void, 4.0),
+            bet(3.0, 50.0, BetStatus::Open, 0.0),
+        ];
+        let s = journal_stats(&bets, 1_000.0);
+        assert_eq!(s.settled, 4);
+        assert!((s.pnl - 6.0).abs() < 1e-12); // +16 - 10
+        assert_eq!(s.turnover, 60.0); // the void stake is not turned over
+        assert_eq!(s.open_stake, 50.0);
+        // in settlement order: won, lost, push, void
+        let expected = [1_000.0, 1_016.0, 1_006.0, 1_006.0, 1_006.0];
+        assert!(s.history.iter().zip(expected.iter()).all(|(a, b)| (a - b).abs() < 1e-9) && s.history.len() == 5);
+        let band = |label: &str| s.bands.iter().find(|b| b.label == label).unwrap();
+        assert_eq!((band("1.50–2.00").bets, band("1.50–2.00").wins), (1, 1));
+        assert_eq!((band("2.00–3.00").bets, band("2.00–3.00").wins), (1, 0)); // the push is left out
+        assert!((band("2.00–3.00").implied - 0.4).abs() < 1e-12);
+        assert_eq!(band("3.00–5.00").bets, 0);
+    }
+
+    #[test]
+    fn devig_methods_remove_the_margin() {
+        let implied = [0.6, 0.5]; // 110% book
+        let sum = |v: &[f64]| v.iter().sum::<f64>();
+        let m = devig(&implied, DevigMethod::Multiplicative);
+        assert!((m[0] - 0.6 / 1.1).abs() < 1e-12 && (sum(&m) - 1.0).abs() < 1e-12);
+        let a = devig(&implied, DevigMethod::Additive);
+        assert!((a[0] - 0.55).abs() < 1e-12 && (a[1] - 0.45).abs() < 1e-12);
+        let p = devig(&implied, DevigMethod::Power);
+        assert!((sum(&p) - 1.0).abs() < 1e-9);
+        // p_i = implied_i^k for one k: equal log ratios
+        assert!((p[0].ln() / implied[0].ln() - p[1].ln() / implied[1].ln()).abs() < 1e-9);
+        assert!(p[0] > m[0]); // the power method takes more of the margin from the longer price
+        assert_eq!(devig(&implied, DevigMethod::None), implied.to_vec());
+        assert_eq!(devig(&[0.55], DevigMethod::Multiplicative), vec![0.55]);
+    }
+
+    #[test]
+    fn bet_clv_compares_taken_odds_with_the_close() {
+        let mut b = BetRecord::new("Chiefs".into(), "ML".into(), String::new(), 2.1, 50.0, 10.0, 0.0);
+        assert!(bet_clv(&b, DevigMethod::Multiplicative).is_none());
+        b.closing = "+100".into(); // any odds format
+        b.closing_others = "2.0".into();
+        let (odds_clv, prob_clv) = bet_clv(&b, DevigMethod::Multiplicative).unwrap();
+        assert!((odds_clv - 0.05).abs() < 1e-12);
+        assert!((prob_clv - (0.5 - 1.0 / 2.1)).abs() < 1e-12);
+        // without the other side there is nothing to de-vig: the closing implied probability is used as is
+        b.closing = "1.9".into();
+        b.closing_others.clear();
+        let (_, prob_clv) = bet_clv(&b, DevigMethod::Multiplicative).unwrap();
+        assert!((prob_clv - (1.0 / 1.9 - 1.0 / 2.1)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn calibration_scores_and_bins() {
+        // 0.7 twice (one win), 0.2 once (loss), 1.0 once (win)
+        let c = calibration(&[(0.7, true), (0.7, false), (0.2, false), (1.0, true)]);
+        assert_eq!(c.n, 4);
+        assert!((c.brier - (0.09 + 0.49 + 0.04 + 0.0) / 4.0).abs() < 1e-12);
+        let expected_ll = -(0.7f64.ln() + 0.3f64.ln() + 0.8f64.ln() + (1.0f64 - 1e-6).ln()) / 4.0;
+        assert!((c.log_loss - expected_ll).abs() < 1e-12);
+        assert_eq!(c.bins[7], (2, 0.7, 0.5));
+        assert_eq!(c.bins[2], (1, 0.2, 0.0));
+        assert_eq!(c.bins[9], (1, 1.0, 1.0)); // 100% falls in the top bin
+        assert_eq!(c.bins.iter().map(|b| b.0).sum::<usize>(), 4);
+        // a certain forecast that misses is penalised but stays finite
+        assert!(calibration(&[(0.0, true)]).log_loss.is_finite());
+        assert_eq!(calibration(&[]).brier, 0.0);
+    }
+
+    #[test]
+    fn shared_state_round_trips_and_fills_missing_fields() {
+        let mut s = AppState { your_prob: 61.5, bankroll: "2500".into(), ..Default::default() };
+        s.compares.push(CompareRow { name: "Over 47.5".into(), group: "Game 1".into(), odds: "+110".into(), your: 51.0, ..Default::default() });
+        s.poisson.home_xg = 2.1;
+        let encoded = encode_state(&s);
+        assert!(encoded.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')); // URL-safe, unpadded
+        assert!(decode_state(&encoded) == Some(s));
+        // a link that only carries some fields loads the rest from the defaults
+        let partial = decode_state(&URL_SAFE_NO_PAD.encode(r#"{"your_prob": 70, "three": []}"#)).unwrap();
+        assert_eq!(partial.your_prob, 70.0);
+        assert!(partial.elo == AppState::default().elo);
+        assert_eq!(partial.validated().three.len(), 3); // panels index three-way rows directly
+        assert!(decode_state("not base64!").is_none());
+        assert!(decode_state(&URL_SAFE_NO_PAD.encode(r#""state""#)).is_none());
+    }
+
+    #[test]
+    fn saved_state_needs_a_known_version() {
+        let s = AppState { your_prob: 48.0, live_url: "ws://example:9001".into(), ..Default::default() };
+        assert!(migrate_state(state_envelope(&s)) == Some(s.clone()));
+        let mut newer = state_envelope(&s);
+        newer["version"] = serde_json::json!(STATE_VERSION + 1);
+        assert!(migrate_state(newer).is_none());
+        // a bare state without the envelope is not guessed at
+        assert!(migrate_state(serde_json::to_value(&s).unwrap()).is_none());
+        assert!(migrate_state(serde_json::json!({ "version": "1", "state": {} })).is_none());
+    }
+
+    #[test]
+    fn preset_scope_changes_only_its_panel() {
+        let current = AppState { bankroll: "5000".into(), your_prob: 50.0, parlay_adj: 5.0, ..Default::default() };
+        let mut preset = AppState { bankroll: "100".into(), your_prob: 65.0, odds_input: "2.4".into(), parlay_adj: -10.0, ..Default::default() };
+        preset.compares[0].odds = "3.0".into();
+        preset.elo.home = 1800.0;
+
+        let single = PresetScope::Single.merge(&current, &preset);
+        assert_eq!((single.your_prob, single.odds_input.as_str()), (65.0, "2.4"));
+        assert_eq!(single.bankroll, "5000");
+        assert!(single.compares == current.compares && single.parlay_adj == 5.0 && single.elo == current.elo);
+
+        let compare = PresetScope::Compare.merge(&current, &preset);
+        assert!(compare.compares == preset.compares && compare.parlay_adj == -10.0);
+        assert!(compare == AppState { compares: preset.compares.clone(), parlay_adj: -10.0, ..current.clone() });
+
+        let models = PresetScope::Models.merge(&current, &preset);
+        assert!(models == AppState { elo: preset.elo.clone(), ..current.clone() });
+
+        assert!(PresetScope::All.merge(&current, &preset) == preset);
+    }
+
+    #[test]
+    fn parse_delimited_keeps_source_line_numbers() {
+        let text = "name;odds\r\n\r\nChiefs;1.91\n\"Over\n47.5\";\"2,05\"\n\nBills;\"say \"\"hi\"\"\"";
+        let records = parse_delimited(text);
+        let lines: Vec<usize> = records.iter().map(|r| r.0).collect();
+        assert_eq!(lines, vec![1, 3, 4, 7]);
+        assert_eq!(records[2].1, vec!["Over\n47.5".to_string(), "2,05".to_string()]);
+        assert_eq!(records[3].1[1], "say \"hi\"");
+        assert_eq!(parse_delimited("a\tb\n1\t2").last().unwrap().1, vec!["1".to_string(), "2".to_string()]);
+    }
+
+    #[test]
+    fn csv_rows_report_source_lines_and_use_the_chosen_odds_format() {
+        let records = parse_delimited("name,odds,prob\n\nChiefs,150,0.55\n,2.0,0.5\nBills,abc,0.4\nRavens,-110,0.52\n");
+        let header = records[0].1.clone();
+        let map = csv_auto_map(&header);
+        let (rows, errors) = csv_compare_rows(&records[1..], &map, Some(OddsFormat::American));
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].odds, "+150"); // signed, so Compare reads 2.5 rather than decimal 150
+        assert_eq!(parse_any(&rows[0].odds), Some(2.5));
+        assert!((rows[0].your - 55.0).abs() < 1e-9); // a column of values ≤ 1 holds fractions
+        assert_eq!(errors, vec!["Row 4: missing name".to_string(), "Row 5 (Bills): invalid odds \"abc\"".to_string()]);
+        // with "Any", 150 is a decimal price
+        let (rows, _) = csv_compare_rows(&records[1..], &map, None);
+        assert_eq!(parse_any(&rows[0].odds), Some(150.0));
+        // a fractional file rejects decimals
+        let (rows, errors) = csv_compare_rows(&parse_delimited("name,odds,prob\nA,5/2,40\nB,2.5,40")[1..], &map, Some(OddsFormat::Fractional));
+        assert_eq!((rows.len(), errors.len()), (1, 1));
+    }
+
+    #[test]
+    fn parse_any_reads_plus_prices_as_american() {
+        assert_eq!(parse_any("+150"), Some(2.5));
+        assert_eq!(parse_any("150"), Some(150.0));
+        assert_eq!(parse_any("-200"), Some(1.5));
+        assert_eq!(parse_any("+1.5"), None); // neither a decimal nor a whole American price
+        assert_eq!(parse_any("3/2"), Some(2.5));
+    }
+
+    const H2H_DECIMAL: &str = include_str!("../tests/fixtures/odds_api_h2h_decimal.json");
+    const SPREADS_TOTALS_AMERICAN: &str = include_str!("../tests/fixtures/odds_api_spreads_totals_american.json");
+
+    #[test]
+    fn odds_feed_builds_a_group_per_event_market_and_a_row_per_outcome_and_book() {
+        let rows = odds_api_rows(H2H_DECIMAL).unwrap();
+        assert_eq!(rows.len(), 9);
+        let arsenal: Vec<&CompareRow> = rows.iter().filter(|r| r.name == "Arsenal").collect();
+        assert_eq!(arsenal.len(), 2);
+        assert_eq!(arsenal[0].group, "EPL: Wolverhampton Wanderers @ Arsenal — Moneyline");
+        assert_eq!((arsenal[0].book.as_str(), arsenal[0].odds.as_str()), ("Pinnacle", "1.3"));
+        assert_eq!((arsenal[1].book.as_str(), arsenal[1].odds.as_str()), ("William Hill", "1.29"));
+        // consensus no-vig probabilities of one market sum to ~100%
+        let group = &arsenal[0].group;
+        let total: f64 = rows.iter().filter(|r| &r.group == group && r.book == "Pinnacle").map(|r| r.your).sum();
+        assert!((total - 100.0).abs() < 0.2, "{}", total);
+        assert_eq!(arsenal[0].your, arsenal[1].your);
+    }
+
+    #[test]
+    fn odds_feed_reads_american_prices_and_names_lines_for_middles() {
+        let rows = odds_api_rows(SPREADS_TOTALS_AMERICAN).unwrap();
+        assert_eq!(rows.len(), 8);
+        let ml = rows.iter().find(|r| r.name == "Baltimore Ravens").unwrap();
+        assert_eq!(parse_any(&ml.odds), Some(2.24));
+        assert!(rows.iter().any(|r| r.name == "Kansas City Chiefs -3" && r.group.ends_with("— Spread")));
+        assert!(rows.iter().any(|r| r.name == "Baltimore Ravens +3"));
+        let over = rows.iter().find(|r| r.name == "Over 46.5").unwrap();
+        assert_eq!(over.book, "DraftKings");
+        assert!((parse_any(&over.odds).unwrap() - (1.0 + 100.0 / 108.0)).abs() < 1e-4);
+        assert!(matches!(parse_line("Under 47.5"), Some((LineKind::Under, _, l)) if l == 47.5));
+        assert!(rows.iter().any(|r| r.name == "Under 47.5" && r.book == "FanDuel"));
+    }
+
+    #[test]
+    fn odds_feed_refresh_keeps_your_probability_and_updates_prices() {
+        let mut rows = odds_api_rows(H2H_DECIMAL).unwrap();
+        rows[0].your = 80.0;
+        let existing = rows.clone();
+        let mut refreshed = odds_api_rows(H2H_DECIMAL).unwrap();
+        refreshed[0].odds = "1.35".into();
+        let merged = merge_compare_rows(&existing, refreshed);
+        assert_eq!(merged.len(), existing.len());
+        assert_eq!((merged[0].your, merged[0].odds.as_str()), (80.0, "1.35"));
+    }
+
+    #[test]
+    fn odds_feed_rejects_other_json() {
+        assert!(odds_api_rows("{\"bets\": []}").is_err());
+        assert!(odds_api_rows("not json").is_err());
+        assert_eq!(odds_api_rows("[]").unwrap().len(), 0);
+    }
+
+    const MARKET_BOOK: &str = include_str!("../tests/fixtures/exchange_market_book.json");
+
+    #[test]
+    fn market_book_keeps_active_runners_with_sorted_ladders() {
+        let ex = market_book_runners(MARKET_BOOK, &ExchangeInputs::default()).unwrap();
+        assert_eq!(ex.market, "1.230558792");
+        assert_eq!(ex.runners.iter().map(|r| r.name.as_str()).collect::<Vec<_>>(), ["Arsenal", "Wolves", "Selection 58805"]);
+        assert_eq!(ex.runners[0].back[0], (1.31, 2150.37));
+        assert_eq!(ex.runners[0].lay[0], (1.32, 1804.12));
+        let total: f64 = ex.runners.iter().map(|r| r.yours).sum();
+        assert!((total - 100.0).abs() < 0.2, "{}", total);
+        // a refresh keeps names and probabilities already entered
+        let mut edited = ex.clone();
+        edited.runners[2].name = "Draw".into();
+        edited.runners[2].yours = 20.0;
+        let again = market_book_runners(MARKET_BOOK, &edited).unwrap();
+        assert_eq!((again.runners[2].name.as_str(), again.runners[2].yours), ("Draw", 20.0));
+        assert!(market_book_runners("[]", &ExchangeInputs::default()).is_err());
+    }
+
+    #[test]
+    fn exchange_stakes_are_capped_by_available_size() {
+        let mut ex = market_book_runners(MARKET_BOOK, &ExchangeInputs::default()).unwrap();
+        ex.commission = 0.0;
+        // Wolves with a 25% view: full Kelly wants far more than the 41.20 at 12.0 and 310 at 11.5 on offer
+        for (r, p) in ex.runners.iter_mut().zip([60.0, 25.0, 15.0]) { r.yours = p; }
+        let calc = exchange_kelly(&ex, 10_000.0);
+        assert!(calc[1].back_capped);
+        assert_eq!(calc[1].back_levels, 2);
+        assert!((calc[1].back_stake - 351.2).abs() < 1e-3, "{}", calc[1].back_stake);
+        assert!((calc[1].back_price - (41.2 * 12.0 + 310.0 * 11.5) / 351.2).abs() < 1e-9);
+        // Arsenal at 60% is worth laying; liability is bounded by what the lay ladder offers
+        assert!(calc[0].lay_liability > 0.0 && calc[0].lay_liability <= 1804.12 * 0.32 + 6120.0 * 0.33 + 1e-6);
+        assert_eq!(calc[0].back_stake, 0.0);
+        // with unlimited size the same view stakes more on Wolves
+        for r in ex.runners.iter_mut() { for l in r.back.iter_mut().chain(r.lay.iter_mut()) { l.1 = 1e9; } }
+        assert!(exchange_kelly(&ex, 10_000.0)[1].back_stake > 351.2);
+    }
+
+    #[test]
+    fn lay_bets_settle_and_measure_clv_from_the_layers_side() {
+        // lay at 4.0 for a backer's stake of 10: liability 30, 2% commission on winnings
+        let mut lay = BetRecord::new("Lay Wolves".into(), "Exchange".into(), String::new(), 4.0, 75.0, 10.0, 0.03).laid().with_commission(2.0);
+        assert_eq!(lay.risk(), 30.0);
+        assert!((lay.effective_odds() - (1.0 + 0.98 / 3.0)).abs() < 1e-12);
+        assert_eq!(lay.implied_pct(), 75.0);
+        lay.settle(BetStatus::Won, 1.0);
+        assert!((lay.profit().unwrap() - 9.8).abs() < 1e-12);
+        lay.settle(BetStatus::Lost, 1.0);
+        assert_eq!(lay.profit(), Some(-30.0));
+        let s = journal_stats(std::slice::from_ref(&lay), 1_000.0);
+        assert_eq!((s.turnover, s.pnl), (30.0, -30.0));
+        assert_eq!(s.bands[0].bets, 1); // effective odds 1.327 on the liability
+
+        // the runner closes at 5.0 against 1.25 for the rest of the market: laying at 4.0 beat the close
+        lay.closing = "5.0".into();
+        lay.closing_others = "1.25".into();
+        let (odds_clv, prob_clv) = bet_clv(&lay, DevigMethod::Multiplicative).unwrap();
+        assert!((odds_clv - 0.25).abs() < 1e-12);
+        assert!((prob_clv - (0.25 - 0.2)).abs() < 1e-12);
+        // a back bet on the same runner at 4.0 lost value
+        let mut back = BetRecord::new("Back Wolves".into(), "Exchange".into(), String::new(), 4.0, 25.0, 10.0, 0.03);
+        back.closing = lay.closing.clone();
+        back.closing_others = lay.closing_others.clone();
+        let (odds_clv, prob_clv) = bet_clv(&back, DevigMethod::Multiplicative).unwrap();
+        assert!(odds_clv < 0.0 && prob_clv < 0.0);
+    }
+
+    #[test]
+    fn exchange_bets_journal_and_export_the_same_market_and_book() {
+        let ex = ExchangeInputs {
+            market: "1.2345".into(),
+            commission: 2.0,
+            runners: vec![
+                ExchangeRunner { id: 1, name: "A".into(), back: vec![(2.4, 500.0)], lay: vec![], yours: 50.0 },
+                ExchangeRunner { id: 2, name: "B".into(), back: vec![], lay: vec![(2.6, 500.0)], yours: 30.0 },
+                ExchangeRunner { id: 3, name: "C".into(), back: vec![], lay: vec![], yours: 20.0 },
+            ],
+        };
+        let calc = exchange_kelly(&ex, 1000.0);
+        let bets: Vec<(BetRecord, &ExchangeCalc)> = ex.runners.iter().zip(calc.iter())
+            .flat_map(|(r, c)| exchange_bets(&ex, r, c).into_iter().flatten().map(move |b| (b, c)))
+            .collect();
+        assert_eq!(bets.iter().map(|(b, _)| b.selection.as_str()).collect::<Vec<_>>(), ["Back A", "Lay B"]);
+        for (b, c) in bets.iter() {
+            let rec = exchange_recommendation(b, 0.0);
+            assert_eq!((b.market.as_str(), b.book.as_str()), ("1.2345", "Exchange"));
+            assert_eq!((&rec.selection, &rec.market, &rec.book), (&b.selection, &b.market, &b.book));
+            assert_eq!((rec.odds_decimal, rec.prob_pct, rec.stake), (b.odds, b.prob, b.stake));
+            if b.lay {
+                assert!((rec.liability.unwrap() - c.lay_liability).abs() < 1e-9);
+            } else {
+                assert_eq!(rec.liability, None);
+            }
+        }
+    }
+
+    #[test]
+    fn live_changed_ignores_moves_that_leave_recommendations_alone() {
+        let moved: HashMap<String, (f64, f64)> = [("a".to_string(), (2.0, 2.1)), ("b".to_string(), (3.0, 3.2)), ("c".to_string(), (1.5, 1.6))].into();
+        let before: LiveRecs = [("a".to_string(), vec![(0, 0.0)]), ("b".to_string(), vec![(1, 0.05)])].into();
+        let now: LiveRecs = [("a".to_string(), vec![(0, 0.0)]), ("b".to_string(), vec![(1, 0.07)])].into();
+        // a stays at no bet, b's stake changed, c was never recorded before its move
+        assert_eq!(live_changed(&moved, &before, &now), vec!["b".to_string()]);
+    }
+
+    #[test]
+    fn live_feed_keeps_the_error_reason_and_expires_only_the_latest_move() {
+        let feed = Rc::new(LiveFeed::default())
+            .reduce(LiveAction::Updates(vec![PriceUpdate { id: "a".into(), odds: 2.0 }]))
+            .reduce(LiveAction::Updates(vec![PriceUpdate { id: "a".into(), odds: 2.2 }]))
+            .reduce(LiveAction::Updates(vec![PriceUpdate { id: "a".into(), odds: 2.4 }]));
+        assert_eq!(feed.moved.get("a"), Some(&(2.2, 2.4)));
+        let feed = feed.reduce(LiveAction::Expire("a".into(), 2.2));
+        assert!(feed.moved.contains_key("a"));
+        let feed = feed.reduce(LiveAction::Expire("a".into(), 2.4));
+        assert!(feed.moved.is_empty());
+
+        let feed = feed.reduce(LiveAction::Status(LiveStatus::Closed("Connection error (ws://x)".into())))
+            .reduce(LiveAction::Status(LiveStatus::Closed("Connection closed (code 1006)".into())));
+        assert!(feed.status == LiveStatus::Closed("Connection error (ws://x)".into()));
+        assert!(feed.reduce(LiveAction::Status(LiveStatus::Connecting)).status == LiveStatus::Connecting);
+    }
+
+    #[test]
+    fn exchange_walks_the_ladder_until_the_marginal_fill_stops_paying() {
+        let ex = ExchangeInputs {
+            market: String::new(),
+            commission: 0.0,
+            runners: vec![
+                ExchangeRunner { id: 1, name: "A".into(), back: vec![(2.0, 10.0), (1.9, 1000.0)], lay: vec![], yours: 55.0 },
+                ExchangeRunner { id: 2, name: "B".into(), back: vec![], lay: vec![], yours: 45.0 },
+            ],
+        };
+        // 10 at 2.0, then 1.9 until 0.55 * 0.9 / W(win) = 0.45 / W(lose): x = 0.03555 / 0.9 of the bankroll
+        let c = &exchange_kelly(&ex, 1000.0)[0];
+        let expected = 10.0 + 1000.0 * 0.03555 / 0.9;
+        assert!((c.back_stake - expected).abs() < 0.05, "{} vs {}", c.back_stake, expected);
+        assert_eq!(c.back_levels, 2);
+        assert!(!c.back_capped);
+        assert!((c.back_price - (10.0 * 2.0 + (expected - 10.0) * 1.9) / expected).abs() < 1e-3);
+        // a single-price book at 2.0 would stake the full 10% Kelly
+        assert!(c.back_stake < 100.0);
+    }
+
+    #[test]
+    fn elo_matches_the_logistic_curve_and_davidson_draws() {
+        let mut e = EloInputs { home: 1500.0, away: 1500.0, home_adv: 0.0, draws: false, draw_pct: 26.0 };
+        assert_eq!(elo_probs(&e), [0.5, 0.0, 0.5]);
+        // 400 points is 10:1 without draws
+        e.home = 1900.0;
+        let [h, d, a] = elo_probs(&e);
+        assert!((h - 10.0 / 11.0).abs() < 1e-12 && d == 0.0 && (a - 1.0 / 11.0).abs() < 1e-12);
+        // Davidson keeps the home/away ratio and the set draw share at equal ratings
+        e.draws = true;
+        let [h, d, a] = elo_probs(&e);
+        assert!((h / a - 10.0).abs() < 1e-9 && (h + d + a - 1.0).abs() < 1e-12);
+        e.home = 1400.0;
+        e.home_adv = 100.0;
+        let [h, d, a] = elo_probs(&e);
+        assert!((d - 0.26).abs() < 1e-12 && (h - a).abs() < 1e-12);
+    }
+
+    #[test]
+    fn poisson_markets_match_closed_forms() {
+        let m = PoissonInputs { home_xg: 1.4, away_xg: 1.1, dixon_coles: false, rho: -0.1, line: 2.5 };
+        let scores = score_matrix(&m);
+        let g = goal_markets(&scores, 2.5);
+        assert!((g.home + g.draw + g.away - 1.0).abs() < 1e-12);
+        // total goals ~ Poisson(2.5); BTTS = (1 - e^-λ)(1 - e^-μ)
+        let t: f64 = 2.5;
+        let under = (-t).exp() * (1.0 + t + t * t / 2.0);
+        assert!((g.under - under).abs() < 1e-6, "{} vs {}", g.under, under);
+        assert_eq!(g.push, 0.0);
+        let btts = (1.0 - (-1.4f64).exp()) * (1.0 - (-1.1f64).exp());
+        assert!((g.btts - btts).abs() < 1e-6);
+        // a whole-number line pushes on the exact total
+        let g3 = goal_markets(&scores, 3.0);
+        assert!((g3.push - (-t).exp() * t.powi(3) / 6.0).abs() < 1e-6);
+        assert!((g3.over + g3.push + g3.under - 1.0).abs() < 1e-12);
+        // goal-difference buckets line up with the 1X2 split
+        let dist = goal_difference_dist(&scores);
+        assert_eq!(dist.len(), AppState::default().asian.dist.len());
+        assert!((dist[(-AH_MIN_GD) as usize] - g.draw).abs() < 1e-12);
+        assert!((dist[..(-AH_MIN_GD) as usize].iter().sum::<f64>() - g.away).abs() < 1e-12);
+    }
+
+    #[test]
+    fn dixon_coles_moves_mass_into_low_scoring_draws() {
+        let mut m = PoissonInputs { home_xg: 1.3, away_xg: 1.3, dixon_coles: false, rho: -0.12, line: 2.5 };
+        let plain = score_matrix(&m);
+        m.dixon_coles = true;
+        let dc = score_matrix(&m);
+        assert!(dc[0][0] > plain[0][0] && dc[1][1] > plain[1][1]);
+        assert!(dc[1][0] < plain[1][0] && dc[0][1] < plain[0][1]);
+        assert!((dc.iter().flatten().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert!(goal_markets(&dc, 2.5).draw > goal_markets(&plain, 2.5).draw);
+    }
+
+    #[test]
+    fn score_matrix_extends_past_ten_goals_for_high_xg() {
+        let m = PoissonInputs { home_xg: 10.0, away_xg: 0.5, dixon_coles: false, rho: 0.0, line: 10.5 };
+        let scores = score_matrix(&m);
+        assert!(scores.len() > POISSON_MIN_GOALS + 1 && scores[0].len() == POISSON_MIN_GOALS + 1);
+        // the home tail left off the grid is below POISSON_TAIL, so the mean is still the xG
+        let home: Vec<f64> = scores.iter().map(|row| row.iter().sum()).collect();
+        assert!(1.0 - home.iter().sum::<f64>() < 1e-12);
+        let mean: f64 = home.iter().enumerate().map(|(k, p)| k as f64 * p).sum();
+        assert!((mean - 10.0).abs() < 1e-6, "{}", mean);
+        // P(home > 10) for Poisson(10) is 1 - 0.583040
+        assert!((home[11..].iter().sum::<f64>() - 0.416960).abs() < 1e-6);
+        // a low xG keeps the minimum grid
+        let low = score_matrix(&PoissonInputs { home_xg: 0.0, away_xg: 0.0, ..m });
+        assert_eq!((low.len(), low[0][0]), (POISSON_MIN_GOALS + 1, 1.0));
+    }
+
+    #[test]
+    fn totals_lines_snap_to_half_goals() {
+        assert_eq!(totals_line(2.5), 2.5);
+        assert_eq!(totals_line(3.0), 3.0);
+        assert_eq!(totals_line(2.25), 2.5);
+        assert_eq!(totals_line(2.7), 2.5);
+        assert_eq!(totals_line(2.8), 3.0);
+        let scores = score_matrix(&PoissonInputs { home_xg: 1.4, away_xg: 1.1, dixon_coles: false, rho: 0.0, line: 2.25 });
+        let g = goal_markets(&scores, totals_line(2.25));
+        assert_eq!(g.push, 0.0);
+        assert!((g.over + g.under - 1.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,43 @@
+// Decimal odds as American and fractional strings
+
+pub fn format_american(d: f64) -> String {
+    if d <= 1.0 { return "—".into(); }
+    let b = d - 1.0;
+    if d >= 2.0 { // positive American odds
+        let n = (b * 100.0).round() as i64;
+        format!("+{}", n)
+    } else {
+        let n = (100.0 / b).round() as i64;
+        format!("-{}", n)
+    }
+}
+
+pub fn format_fractional(d: f64) -> String {
+    if d <= 1.0 { return "—".into(); }
+    let b = d - 1.0;
+    // represent b as a simple fraction with small denominator
+    let (num, den) = approx_fraction(b, 1_000, 100);
+    format!("{}/{}", num, den)
+}
+
+fn approx_fraction(x: f64, max_den: i64, max_iter: i32) -> (i64, i64) {
+    // continued fraction approximation
+    let mut x = x;
+    let mut a0 = x.floor();
+    let mut h0: i64 = 1; let mut k0: i64 = 0;
+    let mut h1: i64 = a0 as i64; let mut k1: i64 = 1;
+    let mut iter = 0;
+    while iter < max_iter {
+        let frac = x - a0;
+        if frac.abs() < 1e-9 { break; }
+        x = 1.0/frac;
+        a0 = x.floor();
+        let h2 = h0 + (a0 as i64)*h1;
+        let k2 = k0 + (a0 as i64)*k1;
+        if k2 > max_den { break; }
+        h0 = h1; k0 = k1; h1 = h2; k1 = k2;
+        iter += 1;
+    }
+    (h1, k1)
+}
+
@@ -0,0 +1,79 @@
+// Runs the mock live-odds server binary and talks to it over a real WebSocket.
+#![cfg(not(target_arch = "wasm32"))]
+
+#[path = "../src/live.rs"]
+mod live;
+
+use live::LiveMessage;
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+use tungstenite::Message;
+
+struct Server(Child);
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+// Start on a free port; the first stdout line carries the URL
+fn start() -> (Server, String) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_mock_odds_server"))
+        .args(["127.0.0.1:0", "--interval", "20"])
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("mock server starts");
+    let mut line = String::new();
+    BufReader::new(child.stdout.take().unwrap()).read_line(&mut line).unwrap();
+    let url = line.split_whitespace().last().expect("url on first line").to_string();
+    assert!(url.starts_with("ws://127.0.0.1:"), "{}", line);
+    (Server(child), url)
+}
+
+fn next_message(socket: &mut tungstenite::WebSocket<tungstenite::stream::MaybeTlsStream<std::net::TcpStream>>) -> LiveMessage {
+    loop {
+        if let Message::Text(text) = socket.read().expect("server message") {
+            return serde_json::from_str(&text).expect("protocol message");
+        }
+    }
+}
+
+#[test]
+fn mock_server_sends_a_snapshot_then_updates_for_subscribed_ids() {
+    let (_server, url) = start();
+    let (mut socket, _) = tungstenite::connect(url.as_str()).expect("connects");
+    let subscribe = LiveMessage::Subscribe { ids: vec!["kc-ml".into(), "bal-ml".into()] };
+    socket.send(Message::Text(serde_json::to_string(&subscribe).unwrap())).unwrap();
+
+    let snapshot = next_message(&mut socket).updates();
+    assert_eq!(snapshot.iter().map(|u| u.id.as_str()).collect::<Vec<_>>(), ["kc-ml", "bal-ml"]);
+    assert!(snapshot.iter().all(|u| u.odds > 1.0));
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let moved = loop {
+        assert!(Instant::now() < deadline, "no price update within 5s");
+        let updates = next_message(&mut socket).updates();
+        if !updates.is_empty() { break updates; }
+    };
+    assert!(moved.iter().all(|u| (u.id == "kc-ml" || u.id == "bal-ml") && u.odds > 1.0));
+
+    // a new subscription replaces the old one
+    let subscribe = LiveMessage::Subscribe { ids: vec!["draw".into()] };
+    socket.send(Message::Text(serde_json::to_string(&subscribe).unwrap())).unwrap();
+    let after = loop {
+        let updates = next_message(&mut socket).updates();
+        if updates.iter().any(|u| u.id == "draw") { break updates; }
+    };
+    assert!(after.iter().all(|u| u.id == "draw"));
+}
+
+#[test]
+fn protocol_messages_round_trip_as_tagged_json() {
+    let msg: LiveMessage = serde_json::from_str(r#"{"type": "price", "id": "a", "odds": 2.5}"#).unwrap();
+    assert_eq!(msg, LiveMessage::Price { id: "a".into(), odds: 2.5 });
+    let json = serde_json::to_string(&LiveMessage::Subscribe { ids: vec!["a".into()] }).unwrap();
+    assert_eq!(json, r#"{"type":"subscribe","ids":["a"]}"#);
+}